@@ -22,7 +22,14 @@ const DESCRIPTION: &str = env!( "CARGO_PKG_DESCRIPTION" );
 
 fn main() -> std::io::Result<()>
 {
-    let config = slint_build::CompilerConfiguration::new().with_style( "cupertino-dark".into() );
+    let config = slint_build::CompilerConfiguration::new()
+        // Light or dark is picked at runtime from the theme in the settings
+        .with_style( "cupertino".into() )
+        // Shared with the Rust side, so messages are looked up without the component name as context
+        .with_bundled_translations( "lang" )
+    .with_default_translation_context( slint_build::DefaultTranslationContext::None );
+    // The .po files aren't imported by the .slint file, so changes to them aren't picked up otherwise
+    println!( "cargo:rerun-if-changed=lang" );
 
     if let Err(e) = 
     slint_build::compile_with_config( format!( "ui/{}.slint", PRODUCT_NAME ), config )
@@ -0,0 +1,324 @@
+/*
+	Sven Co-op Plugin Manager Version 2.0
+
+Copyright (C) 2025 Outerbeast
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program. If not, see <https://www.gnu.org/licenses/>.
+*/
+use std::
+{
+    collections::HashSet,
+    io,
+    path::{ Path, PathBuf },
+    sync::{ Arc, Mutex },
+    thread
+};
+
+use slint::
+{
+    CloseRequestResponse,
+    ComponentHandle,
+    ModelRc,
+    PlatformError,
+    VecModel
+};
+
+use crate::
+{
+    alloc_locked,
+    catalogue::{ self, CatalogueEntry },
+    config,
+    plugin::PluginEntry,
+    tr,
+    trn,
+    CatalogueRow
+};
+
+use super::
+{
+    ask,
+    theme,
+    PopupButtons,
+    PopupChoice
+};
+
+pub type CatalogueWindow = crate::CatalogueWindow;
+// Files listed when asking to replace them, any more are counted
+const MAX_FILES_LISTED: usize = 10;
+
+#[derive( Default )]
+struct BrowserState
+{
+    index: String,// where the plugins were loaded from, archives are relative to it
+    plugins: Vec<CatalogueEntry>,
+    shown: Vec<usize>,// indexes into plugins of the rows shown
+    installed: HashSet<String>,// lowercase plugin names already in the plugin list, or installed here
+    done: Vec<(String, String)>// (name, script) of every plugin installed here, to be registered
+}
+
+impl BrowserState
+{   // Rows for the plugins matching the filter, in catalogue order
+    fn rows(&mut self, filter: &str) -> Vec<CatalogueRow>
+    {
+        self.shown = ( 0..self.plugins.len() ).filter( |i| self.plugins[*i].matches( filter ) ).collect();
+
+        self.shown.iter().map( |i|
+        {
+            let p = &self.plugins[*i];
+
+            CatalogueRow
+            {
+                name: p.name.as_str().into(),
+                author: p.author.as_str().into(),
+                version: p.version.as_str().into(),
+                description: p.description.as_str().into(),
+                installed: self.installed.contains( &p.name.to_lowercase() )
+            }
+        })
+        .collect()
+    }
+}
+
+fn set_status(window: &CatalogueWindow, text: &str, error: bool)
+{
+    window.set_status_text( text.into() );
+    window.set_status_error( error );
+}
+// Refills the list, keeping the plugin that was selected if it is still shown
+fn show_rows(window: &CatalogueWindow, state: &mut BrowserState)
+{
+    let current = usize::try_from( window.get_current() ).ok().and_then( |i| state.shown.get( i ).copied() );
+    let rows = state.rows( &window.get_filter_text() );
+
+    let current =
+    match current.and_then( |c| state.shown.iter().position( |i| *i == c ) )
+    {
+        Some( i ) => i as i32,
+        None if rows.is_empty() => -1,
+        None => 0
+    };
+
+    window.set_rows( ModelRc::new( VecModel::from( rows ) ) );
+    window.set_current( current );
+}
+// Reads the index on another thread, so a slow server doesn't hold up the window
+fn load(window: &CatalogueWindow, state: &Arc<Mutex<BrowserState>>)
+{
+    let location = window.get_location().trim().to_string();
+
+    if location.is_empty()
+    {
+        set_status( window, &tr!( "Enter the file or URL of a catalogue index" ), true );
+        return;
+    }
+
+    window.set_busy( true );
+    set_status( window, &tr!( "Loading {}...", location ), false );
+
+    let window_weak = window.as_weak();
+    let state = state.clone();
+
+    thread::spawn( move ||
+    {
+        let loaded = catalogue::load_catalogue( &location );
+
+        let _ = window_weak.upgrade_in_event_loop( move |w|
+        {
+            w.set_busy( false );
+
+            let loaded =
+            match loaded
+            {
+                Ok( loaded ) => loaded,
+                Err( e ) =>
+                {
+                    set_status( &w, &tr!( "Could not load the catalogue: {}", e ), true );
+                    return;
+                }
+            };
+
+            let Ok( mut state ) = state.lock() else { return };
+            state.index = location.clone();
+            state.plugins = loaded.plugins;
+            state.shown.clear();
+
+            w.set_current( -1 );
+            show_rows( &w, &mut state );
+            set_status( &w, &trn!( "{} plugin in the catalogue", "{} plugins in the catalogue", state.plugins.len() ), false );
+            // Opened straight away next time
+            if let Ok( mut store ) = config::read_store()
+            && store.settings.catalogue != location
+            {
+                store.settings.catalogue = location;
+                let _ = config::write_store( &store );
+            }
+        });
+    });
+}
+// Downloads the plugin on another thread, then copies it in once any files it would replace are agreed to.
+// It is registered once the window is closed.
+fn install(row: i32, window: &CatalogueWindow, state: &Arc<Mutex<BrowserState>>, svencoop_dir: &Path)
+{
+    let Ok( guard ) = state.lock() else { return };
+    let Some( entry ) = usize::try_from( row ).ok().and_then( |r| guard.shown.get( r ) ).map( |i| guard.plugins[*i].clone() ) else { return };
+    let index = guard.index.clone();
+    drop( guard );
+
+    window.set_busy( true );
+    set_status( window, &tr!( "Installing {}...", entry.name ), false );
+
+    let window_weak = window.as_weak();
+    let state = state.clone();
+    let svencoop_dir = svencoop_dir.to_path_buf();
+
+    thread::spawn( move ||
+    {
+        let downloaded = catalogue::download( &entry, &index );
+
+        let _ = window_weak.upgrade_in_event_loop( move |w|
+        {
+            let installed = downloaded.and_then( |download|
+            {
+                let collisions = download.collisions( &svencoop_dir );
+
+                if !collisions.is_empty() && !confirm_replace( &entry.name, &collisions )
+                {
+                    return Err( io::Error::new( io::ErrorKind::AlreadyExists, tr!( "Nothing was installed, files already in svencoop_addon were kept" ) ) );
+                }
+
+                download.files().try_for_each( |( staged, relative )| PluginEntry::install_addon_file( &staged, relative, &svencoop_dir ) )?;
+
+                Ok( download.script().to_string() )
+            });
+
+            w.set_busy( false );
+
+            match installed
+            {
+                Ok( script ) =>
+                {
+                    let Ok( mut state ) = state.lock() else { return };
+                    state.installed.insert( entry.name.to_lowercase() );
+                    state.done.push( ( entry.name.clone(), script ) );
+
+                    show_rows( &w, &mut state );
+                    set_status( &w, &tr!( "{} installed, it is added to the plugin list when this window is closed", entry.name ), false );
+                }
+
+                Err( e ) => set_status( &w, &tr!( "Could not install {}: {}", entry.name, e ), true )
+            }
+        });
+    });
+}
+// Asks before files someone may have changed are written over, `files` are from svencoop_addon
+fn confirm_replace(name: &str, files: &[&Path]) -> bool
+{
+    let mut list: Vec<String> = files.iter().take( MAX_FILES_LISTED ).map( |f| f.display().to_string() ).collect();
+
+    if files.len() > MAX_FILES_LISTED
+    {
+        list.push( tr!( "...and {} more", files.len() - MAX_FILES_LISTED ) );
+    }
+
+    ask( &tr!( "Replace Files" ),
+        &tr!( "Installing {} would replace these files in svencoop_addon:\n\n{}\n\nReplace them?", name, list.join( "\n" ) ),
+        "⚠️",
+        PopupButtons::YesNo ) == PopupChoice::Yes
+}
+// Shows the catalogue from the index in the settings. Plugins already in `installed` (lowercase names) can't be installed again.
+// Returns (name, script) of every plugin installed while it was open, their files are already in svencoop_addon.
+pub fn browse_catalogue(svencoop_dir: &Path, installed: HashSet<String>) -> Result<Vec<(String, String)>, PlatformError>
+{
+    let window = CatalogueWindow::new()?;
+    theme::apply( &window );
+
+    let state = alloc_locked!( BrowserState { installed, ..Default::default() } );
+    let location = config::read_store().unwrap_or_default().settings.catalogue;
+    window.set_location( location.as_str().into() );
+
+    let window_weak = window.as_weak();
+    let state_clone = state.clone();
+    window.on_load( move ||
+    {
+        if let Some( w ) = window_weak.upgrade()
+        {
+            load( &w, &state_clone );
+        }
+    });
+
+    let window_weak = window.as_weak();
+    window.on_browse( move ||
+    {
+        let Some( w ) = window_weak.upgrade() else { return };
+
+        if let Some( path ) = rfd::FileDialog::new()
+            .set_title( tr!( "Select a catalogue index" ) )
+            .add_filter( tr!( "Catalogue index" ), &["json"] )
+        .pick_file()
+        {
+            w.set_location( path.display().to_string().into() );
+            w.invoke_load();
+        }
+    });
+
+    let window_weak = window.as_weak();
+    let state_clone = state.clone();
+    window.on_filter_changed( move ||
+    {
+        if let Some( w ) = window_weak.upgrade()
+        && let Ok( mut state ) = state_clone.lock()
+        {
+            show_rows( &w, &mut state );
+        }
+    });
+
+    let window_weak = window.as_weak();
+    let state_clone = state.clone();
+    let svencoop_dir: PathBuf = svencoop_dir.to_path_buf();
+    window.on_install( move |row|
+    {
+        if let Some( w ) = window_weak.upgrade()
+        {
+            install( row, &w, &state_clone, &svencoop_dir );
+        }
+    });
+
+    let window_weak = window.as_weak();
+    window.on_close( move ||
+    {
+        if let Some( w ) = window_weak.upgrade()
+        {
+            let _ = w.hide();
+        }
+    });
+
+    // Kept open until an install going on is done, or its plugin would never be registered
+    let window_weak = window.as_weak();
+    window.window().on_close_requested( move ||
+    {
+        match window_weak.upgrade().is_some_and( |w| w.get_busy() )
+        {
+            true => CloseRequestResponse::KeepWindowShown,
+            false => CloseRequestResponse::HideWindow
+        }
+    });
+
+    if !location.trim().is_empty()
+    {
+        load( &window, &state );
+    }
+
+    window.run()?;
+
+    Ok( state.lock().map( |mut s| std::mem::take( &mut s.done ) ).unwrap_or_default() )
+}
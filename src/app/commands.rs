@@ -0,0 +1,136 @@
+/*
+	Sven Co-op Plugin Manager Version 2.0
+
+Copyright (C) 2025 Outerbeast
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program. If not, see <https://www.gnu.org/licenses/>.
+*/
+// Everything the window can do from the keyboard, for the shortcuts and the command palette.
+// Each command goes through the same controller function as the button or menu it stands in for.
+use std::
+{
+    cell::RefCell,
+    rc::Rc
+};
+
+use slint::{ ModelRc, VecModel };
+
+use crate::
+{
+    plugin::PluginState,
+    tr,
+    utils,
+    Command,
+    PaletteEntry
+};
+
+use super::
+{
+    controller,
+    AppWindow,
+    PluginContext
+};
+
+// Commands in the order the palette lists them, with their shortcut if they have one
+fn all_commands() -> Vec<( Command, String, &'static str )>
+{
+    vec!
+    [
+        ( Command::ToggleEnabled, tr!( "Enable or disable the selected plugins" ), "Space" ),
+        ( Command::AddPlugin, tr!( "Add a plugin" ), "Ctrl+N" ),
+        ( Command::Remove, tr!( "Remove the selected plugins" ), "Delete" ),
+        ( Command::Save, tr!( "Save" ), "Ctrl+S" ),
+        ( Command::SaveExit, tr!( "Save and exit" ), "" ),
+        ( Command::FocusSearch, tr!( "Search plugins" ), "Ctrl+F" ),
+        ( Command::EditDetails, tr!( "Edit the selected plugin" ), "Enter" ),
+        ( Command::SelectAll, tr!( "Select all" ), "" ),
+        ( Command::ClearSelection, tr!( "Clear selection" ), "Esc" ),
+        ( Command::ViewSource, tr!( "View source" ), "" ),
+        ( Command::OpenInEditor, tr!( "Open in editor" ), "" ),
+        ( Command::RevealScript, tr!( "Reveal in file manager" ), "" ),
+        ( Command::PickIncludedMaps, tr!( "Pick included maps" ), "" ),
+        ( Command::PickExcludedMaps, tr!( "Pick excluded maps" ), "" ),
+        ( Command::AddInstall, tr!( "Add install" ), "" ),
+        ( Command::ServerSettings, tr!( "Server settings" ), "" ),
+        ( Command::ReloadServerPlugins, tr!( "Reload plugins on the server" ), "" ),
+        ( Command::BrowseCatalogue, tr!( "Browse the plugin catalogue" ), "" ),
+        ( Command::Settings, tr!( "Settings" ), "" ),
+        ( Command::ShowNotifications, tr!( "Show notifications" ), "" )
+    ]
+}
+// Commands whose title has every word of the filter in it
+pub fn palette_entries(filter: &str) -> Vec<PaletteEntry>
+{
+    all_commands().into_iter()
+        .filter( |( _, title, _ )| filter.split_whitespace().all( |word| utils::fuzzy_match( word, title ) ) )
+        .map( |( command, title, shortcut )| PaletteEntry { title: title.into(), shortcut: shortcut.into(), command } )
+    .collect()
+}
+
+pub(crate) fn on_palette_filter_changed(app: &AppWindow)
+{
+    app.set_palette_entries( ModelRc::new( VecModel::from( palette_entries( &app.get_palette_filter() ) ) ) );
+}
+
+pub(crate) fn run_command(command: Command, app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    let selected = plugin_data.borrow().selected_plugin_name.clone();
+    let single = app.get_selection_count() == 1;
+
+    match command
+    {
+        Command::ToggleEnabled =>
+        {   // A mixed selection is enabled, the same way a tri-state checkbox would go
+            let data = plugin_data.borrow();
+            let enable = data.selected.iter()
+                .filter_map( |k| data.plugins.get( k ) )
+            .any( |p| p.state == PluginState::Disabled );
+            drop( data );
+
+            controller::on_bulk_set_enabled( enable, app, plugin_data );
+        }
+
+        Command::Remove => controller::on_remove_clicked( app, plugin_data ),
+        Command::Save => { let _ = controller::on_save_clicked( app, plugin_data ); }
+        Command::SaveExit => controller::on_save_exit_clicked( app, plugin_data ),
+        Command::AddPlugin => controller::on_add_clicked( app, plugin_data ),
+        Command::FocusSearch => app.invoke_focus_search(),
+        Command::EditDetails if single => app.invoke_edit_details(),
+        Command::SelectAll => controller::on_select_all( app, plugin_data ),
+        Command::ClearSelection => controller::on_clear_selection( app, plugin_data ),
+        Command::ViewSource => controller::on_view_source_clicked( app, plugin_data ),
+        Command::OpenInEditor | Command::RevealScript =>
+        {
+            if let Some( key ) = selected
+            {
+                let action = if command == Command::OpenInEditor { 0 } else { 1 };
+                controller::on_row_menu( &key, action, app, plugin_data );
+            }
+        }
+
+        Command::PickIncludedMaps if single => controller::on_pick_maps( 0, app, plugin_data ),
+        Command::PickExcludedMaps if single => controller::on_pick_maps( 1, app, plugin_data ),
+        Command::AddInstall => controller::on_add_install_clicked( app, plugin_data ),
+        Command::ServerSettings => controller::on_server_clicked( app ),
+        Command::ReloadServerPlugins => controller::on_reload_server_clicked( app ),
+        Command::BrowseCatalogue => controller::on_catalogue_clicked( app, plugin_data ),
+        Command::Settings => controller::on_settings_clicked( app, plugin_data ),
+        Command::ShowNotifications =>
+        {
+            app.set_log_visible( true );
+            app.set_unread_count( 0 );
+        }
+
+        _ => {}
+    }
+}
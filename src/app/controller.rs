@@ -18,110 +18,524 @@ along with this program. If not, see <https://www.gnu.org/licenses/>.
 use std::
 {
     cell::RefCell,
-    collections::HashMap,
-    path::PathBuf,
-    rc::Rc
+    rc::Rc,
+    thread
 };
 
 use rfd;
 
 use slint::
 {
+    CloseRequestResponse,
     ComponentHandle,
+    Model,
     VecModel,
-    StandardListViewItem,
     ModelRc,
-    PlatformError
+    PhysicalPosition,
+    PhysicalSize,
+    PlatformError,
+    SharedString
 };
 
 use crate::
 {
-    config::SVENCOOP_PATH,
+    config::{ self, Install, RconSettings, SortColumn, SVENCOOP_PATH },
+    health::ScriptHealth,
     plugin::
     {
         PluginEntry,
         PluginState,
         AdminLevel,
+        DiskSnapshot,
+        check_concommandns,
+        check_map_list,
+        check_plugin_name,
+        check_script_path,
+        MapFilter,
+        addon_plugins_dir,
+        split_maps,
+        merge_plugins,
+        resolve_script_path,
+        read_plugin_files,
         save_plugins,
-        CHECKED,
-        UNCHECKED,
-    }
+    },
+    rcon,
+    tr,
+    trn,
+    utils,
+    PluginRow
 };
 
 use super::
 {
+    catalogue_browser,
+    map_picker,
+    rcon_settings,
+    script_viewer,
+    settings,
+    theme,
+    ask,
+    notify,
     popup,
     AppWindow,
+    NotifyLevel,
     PluginContext,
     PopupButtons,
+    PopupChoice,
 };
 
-pub fn make_plugin_list(plugins: &HashMap<String, PluginEntry>) -> Vec<StandardListViewItem>
+// The rest of a long list of compile errors is left to the server log
+const MAX_COMPILE_ERRORS_SHOWN: usize = 5;
+
+fn sort_column_from_ui(column: i32) -> SortColumn
+{
+    match column
+    {
+        0 => SortColumn::State,
+        2 => SortColumn::Script,
+        _ => SortColumn::Name
+    }
+}
+
+// 0 = not checked yet, 1 = found, 2 = shadowed, 3 = missing, 4 = failing lint
+fn health_to_ui(health: Option<&ScriptHealth>) -> i32
+{
+    match health
+    {
+        None => 0,
+        Some( ScriptHealth::Base( _ ) | ScriptHealth::Addon( _ ) ) => 1,
+        Some( ScriptHealth::Shadowed { .. } ) => 2,
+        Some( ScriptHealth::Missing ) => 3,
+        Some( ScriptHealth::Lint { .. } ) => 4
+    }
+}
+
+fn sort_column_to_ui(column: SortColumn) -> i32
+{
+    match column
+    {
+        SortColumn::State => 0,
+        SortColumn::Name => 1,
+        SortColumn::Script => 2
+    }
+}
+// What the plugin list is narrowed down to: words typed in the search box plus the quick filters
+#[derive( Debug, Default )]
+pub struct PluginFilter
+{
+    pub text: String,
+    pub enabled: bool,
+    pub disabled: bool,
+    pub missing_script: bool,
+    pub map_restricted: bool
+}
+
+impl PluginFilter
+{
+    fn from_ui(app: &AppWindow) -> Self
+    {
+        Self
+        {
+            text: app.get_filter_text().to_string(),
+            enabled: app.get_filter_enabled(),
+            disabled: app.get_filter_disabled(),
+            missing_script: app.get_filter_missing_script(),
+            map_restricted: app.get_filter_map_restricted()
+        }
+    }
+
+    // `health` is the plugin's script as last checked in the background, None until it has been
+    pub fn matches(&self, plugin: &PluginEntry, health: Option<&ScriptHealth>) -> bool
+    {   // Enabled and disabled narrow down by state, ticking both is the same as ticking neither
+        if self.enabled != self.disabled
+        && ( plugin.state == PluginState::Enabled ) != self.enabled
+        {
+            return false;
+        }
+
+        if self.map_restricted && plugin.maps_included.trim().is_empty() && plugin.maps_excluded.trim().is_empty()
+        {
+            return false;
+        }
+        // Every word has to be found in the name, script or command namespace
+        let found = self.text.split_whitespace().all( |word|
+        {
+            [&plugin.name, &plugin.script, &plugin.concommandns].iter().any( |field| utils::fuzzy_match( word, field ) )
+        });
+        found && ( !self.missing_script || matches!( health, Some( ScriptHealth::Missing ) ) )
+    }
+}
+// Rows for the plugin list, sorted and with anything not matching the filter left out
+pub fn make_plugin_list(data: &PluginContext, sort: SortColumn, descending: bool, filter: &PluginFilter) -> Vec<PluginRow>
 {
-    let mut list: Vec<(String, bool)> = plugins
-        .values()
-        .map( |p| ( p.name.clone(), p.state == PluginState::Enabled ) )
+    let mut list: Vec<(&String, &PluginEntry)> = data.plugins
+        .iter()
+        .filter( |(_, p)| filter.matches( p, data.health.get( &p.script ).as_ref() ) )
     .collect();
     
-    list.sort_unstable_by_key( |(name, _)| name.to_ascii_lowercase() );
+    list.sort_unstable_by_key( |(_, p)|
+    {
+        let name = p.name.to_ascii_lowercase();
+
+        match sort
+        {
+            SortColumn::State => ( p.state != PluginState::Enabled, name ),
+            SortColumn::Name => ( false, name ),
+            SortColumn::Script => ( false, format!( "{}\0{}", p.script.to_ascii_lowercase(), name ) )
+        }
+    });
+
+    if descending
+    {
+        list.reverse();
+    }
     
-    list.into_iter().map( |(name, enabled)|
+    list.into_iter().map( |(key, p)|
     {
-        let display = format!( "{} {}", if enabled { CHECKED } else { UNCHECKED }, name );
-        StandardListViewItem::from( display.as_str() )
+        let status = data.health.get( &p.script );
+
+        PluginRow
+        {
+            key: key.as_str().into(),
+            name: p.name.as_str().into(),
+            script: p.script.as_str().into(),
+            enabled: p.state == PluginState::Enabled,
+            selected: data.selected.contains( key ),
+            modified: data.is_modified( key ),
+            health: health_to_ui( status.as_ref() ),
+            health_tip: status.map( |s| s.describe() ).unwrap_or_else( || tr!( "Checking..." ) ).into()
+        }
     })
     .collect()
 }
 
 pub(crate) fn refresh_plugin_list(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
 {
-    app.set_plugin_list( ModelRc::new( VecModel::from( make_plugin_list( &plugin_data.borrow().plugins ) ) ) );
+    let data = plugin_data.borrow();
+    data.health.track( data.plugins.values().map( |p| p.script.as_str() ) );
+
+    let rows = make_plugin_list( &data,
+        sort_column_from_ui( app.get_sort_column() ),
+        app.get_sort_descending(),
+        &PluginFilter::from_ui( app ) );
+
+    app.set_list_count(
+    match rows.len() == data.plugins.len()
+    {
+        true => trn!( "{} plugin", "{} plugins", rows.len() ),
+        false => trn!( "{} of {} plugin", "{} of {} plugins", data.plugins.len(), rows.len(), data.plugins.len() )
+    }.into() );
+
+    // Refill the model already shown rather than swapping it, the list keeps its scroll position
+    match app.get_plugin_rows().as_any().downcast_ref::<VecModel<PluginRow>>()
+    {
+        Some( model ) => model.set_vec( rows ),
+        None => app.set_plugin_rows( ModelRc::new( VecModel::from( rows ) ) )
+    }
+    app.set_selection_count( data.selected.len() as i32 );
+    app.set_dirty( data.has_unsaved_changes() );
+    drop( data );
+    // Compile errors come in with the script status, so the detail panel picks them up here too
+    show_compile_errors( app, plugin_data );
+}
+// Marks the selected rows without rebuilding the list, so it stays scrolled where it is
+fn refresh_row_selection(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    let data = plugin_data.borrow();
+    let rows = app.get_plugin_rows();
+
+    for i in 0..rows.row_count()
+    {
+        if let Some( mut row ) = rows.row_data( i )
+        && row.selected != data.selected.contains( row.key.as_str() )
+        {
+            row.selected = !row.selected;
+            rows.set_row_data( i, row );
+        }
+    }
+
+    app.set_selection_count( data.selected.len() as i32 );
+}
+// Keys of the rows currently shown, in the order they are shown
+fn visible_keys(app: &AppWindow) -> Vec<String>
+{
+    app.get_plugin_rows().iter().map( |row| row.key.to_string() ).collect()
+}
+
+// Fills the detail panel from a plugin, or clears it when there is none
+fn show_plugin_details(app: &AppWindow, plugin: Option<&PluginEntry>)
+{
+    let empty = PluginEntry::new( "", "" );
+    let plugin = plugin.unwrap_or( &empty );
+
+    app.set_txt_name( plugin.name.clone().into() );
+    app.set_txt_script( plugin.script.clone().into() );
+    app.set_txt_concommandns( plugin.concommandns.clone().into() );
+    app.set_cb_adminlevel( plugin.adminlevel as i32 );
+    app.set_txt_maps_included( plugin.maps_included.clone().into() );
+    app.set_txt_maps_excluded( plugin.maps_excluded.clone().into() );
+    app.set_chk_enabled( !plugin.name.is_empty() && plugin.state == PluginState::Enabled );
+
+    for set_error in [AppWindow::set_err_name,
+        AppWindow::set_err_script,
+        AppWindow::set_err_concommandns,
+        AppWindow::set_err_maps_included,
+        AppWindow::set_err_maps_excluded]
+    {
+        set_error( app, SharedString::new() );
+    }
 }
 
-pub(crate) fn on_plugin_selected(index: i32, app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+// Lists the compiler errors the server logged for the plugin in the detail panel
+fn show_compile_errors(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
 {
     let data = plugin_data.borrow();
-    let mut sorted_plugins: Vec<_> = data.plugins.values().collect::<Vec<_>>();
-    sorted_plugins.sort_unstable_by_key( |p| p.name.to_ascii_lowercase() );
-    
-    if let Some( plugin ) = sorted_plugins.get( index as usize )
-    {
-        let name = plugin.name.clone();
-        let script = plugin.script.clone();
-        let concommandns = plugin.concommandns.clone();
-        let adminlevel = plugin.adminlevel as i32;
-        let maps_included = plugin.maps_included.clone();
-        let maps_excluded = plugin.maps_excluded.clone();
-        let enabled = plugin.state == PluginState::Enabled;
-        
-        drop( data );
-        
-        let mut data = plugin_data.borrow_mut();
-        data.selected_plugin_name = Some( name.clone() );
-        
-        app.set_txt_name( name.into() );
-        app.set_txt_script( script.into() );
-        app.set_txt_concommandns( concommandns.into() );
-        app.set_cb_adminlevel( adminlevel );
-        app.set_txt_maps_included( maps_included.into() );
-        app.set_txt_maps_excluded( maps_excluded.into() );
-        app.set_chk_enabled( enabled );
+    let errors = data.selected_plugin_name
+        .as_ref()
+        .and_then( |k| data.plugins.get( k ) )
+        .map( |p| data.health.errors( &p.script ) )
+    .unwrap_or_default();
+
+    let mut lines: Vec<SharedString> = errors.iter().take( MAX_COMPILE_ERRORS_SHOWN ).map( |e| e.describe().into() ).collect();
+
+    if errors.len() > MAX_COMPILE_ERRORS_SHOWN
+    {
+        lines.push( tr!( "...and {} more", errors.len() - MAX_COMPILE_ERRORS_SHOWN ).into() );
+    }
+
+    app.set_compile_errors( ModelRc::new( VecModel::from( lines ) ) );
+}
+
+// Brings the detail panel and the list in line with the selection
+fn show_selection(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    let data = plugin_data.borrow();
+    show_plugin_details( app, data.selected_plugin_name.as_ref().and_then( |k| data.plugins.get( k ) ) );
+    drop( data );
+
+    show_compile_errors( app, plugin_data );
+
+    refresh_row_selection( app, plugin_data );
+}
+
+pub(crate) fn select_plugin(key: &str, app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    if plugin_data.borrow().has_plugin( key )
+    {
+        plugin_data.borrow_mut().select_only( key );
+        show_selection( app, plugin_data );
+    }
+}
+// Plain click selects just the one row, ctrl adds or takes it away, shift selects everything from the last row clicked
+pub(crate) fn on_row_clicked(key: &str, shift: bool, ctrl: bool, app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    let mut data = plugin_data.borrow_mut();
+
+    if !data.has_plugin( key )
+    {
+        return;
+    }
+
+    let keys = visible_keys( app );
+    let range = data.selection_anchor
+        .as_ref()
+        .and_then( |anchor| keys.iter().position( |k| k == anchor ) )
+        .zip( keys.iter().position( |k| k == key ) )
+    .filter( |_| shift );
+
+    if let Some( ( from, to ) ) = range
+    {
+        if !ctrl
+        {
+            data.selected.clear();
+        }
+
+        data.selected.extend( keys[from.min( to )..=from.max( to )].iter().cloned() );
+        data.selected_plugin_name = Some( key.to_string() );
+    }
+    else if ctrl
+    {
+        if data.selected.remove( key )
+        {
+            data.selected_plugin_name = data.selected.iter().next().cloned();
+        }
+        else
+        {
+            data.selected.insert( key.to_string() );
+            data.selected_plugin_name = Some( key.to_string() );
+        }
+
+        data.selection_anchor = Some( key.to_string() );
+    }
+    else
+    {
+        data.select_only( key );
+    }
+
+    drop( data );
+    show_selection( app, plugin_data );
+}
+
+// Arrow keys move the selection through the list, with shift selecting everything from where it started
+pub(crate) fn on_move_selection(delta: i32, shift: bool, app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    let keys = visible_keys( app );
+
+    if keys.is_empty()
+    {
+        return;
+    }
+
+    let current = plugin_data.borrow().selected_plugin_name.as_ref().and_then( |key| keys.iter().position( |k| k == key ) );
+
+    let target =
+    match current
+    {
+        Some( i ) => ( i as i64 + delta as i64 ).clamp( 0, keys.len() as i64 - 1 ) as usize,
+        None if delta < 0 => keys.len() - 1,
+        None => 0
+    };
+
+    on_row_clicked( &keys[target], shift, false, app, plugin_data );
+    app.invoke_scroll_to_row( target as i32 );
+}
+
+pub(crate) fn on_select_all(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    let keys = visible_keys( app );
+    let mut data = plugin_data.borrow_mut();
+
+    if data.selected_plugin_name.as_ref().is_none_or( |k| !keys.contains( k ) )
+    {
+        data.selected_plugin_name = keys.first().cloned();
+    }
+
+    data.selection_anchor = keys.first().cloned();
+    data.selected = keys.into_iter().collect();
+    drop( data );
+
+    show_selection( app, plugin_data );
+}
+
+pub(crate) fn on_clear_selection(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    plugin_data.borrow_mut().clear_selection();
+    show_selection( app, plugin_data );
+}
+// Applies the same change to every selected plugin
+fn edit_selected<F>(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>, mut edit: F)
+where F: FnMut(&mut PluginEntry)
+{
+    let mut data = plugin_data.borrow_mut();
+    let ctx = &mut *data;
+
+    for key in &ctx.selected
+    {
+        if let Some( plugin ) = ctx.plugins.get_mut( key )
+        {
+            edit( plugin );
+        }
+    }
+
+    drop( data );
+    refresh_plugin_list( app, plugin_data );
+    show_selection( app, plugin_data );
+}
+
+pub(crate) fn on_bulk_set_enabled(enabled: bool, app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    edit_selected( app, plugin_data, |plugin|
+    {
+        if plugin.state != PluginState::Removed
+        {
+            plugin.state = if enabled { PluginState::Enabled } else { PluginState::Disabled };
+        }
+    });
+}
+
+pub(crate) fn on_bulk_set_adminlevel(level: i32, app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    let level = AdminLevel::from( level as isize );
+    edit_selected( app, plugin_data, |plugin| plugin.adminlevel = level );
+}
+// Several maps can be given at once, separated the same way as in the map lists
+pub(crate) fn on_bulk_edit_map(maps: &str, list: i32, add: bool, app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    let filter = if list == 1 { MapFilter::Excluded } else { MapFilter::Included };
+    let maps = split_maps( maps );
+
+    if maps.is_empty()
+    {
+        return;
     }
+
+    edit_selected( app, plugin_data, |plugin|
+    {
+        for map in &maps
+        {
+            match add
+            {
+                true => plugin.add_map( filter, map ),
+                false => plugin.remove_map( filter, map )
+            };
+        }
+    });
 }
 
-pub(crate) fn on_script_clicked(app: &AppWindow)
+// Opens the map picker for the selected plugin's included (0) or excluded (1) maps
+pub(crate) fn on_pick_maps(list: i32, app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
 {
-    let parent = SVENCOOP_PATH
-        .get()
-        .expect( "SVENCOOP_PATH missing" )
-        .parent()
-        .expect( "missing parent" );
+    let Some( svencoop_path ) = SVENCOOP_PATH.get() else { return };
+    let Some( key ) = plugin_data.borrow().selected_plugin_name.clone() else { return };
+
+    let ( filter, text, heading ) =
+    match list
+    {
+        1 => ( MapFilter::Excluded, app.get_txt_maps_excluded(), tr!( "Excluded maps for {}", key ) ),
+        _ => ( MapFilter::Included, app.get_txt_maps_included(), tr!( "Included maps for {}", key ) )
+    };
+    // Start from the field, so anything typed in that hasn't passed its check yet isn't lost
+    let current: Vec<String> = split_maps( &text ).into_iter().map( str::to_string ).collect();
+
+    let chosen =
+    match map_picker::pick_maps( &svencoop_path, &heading, &current )
+    {
+        Ok( Some( chosen ) ) => chosen,
+        Ok( None ) => return,
+        Err( e ) =>
+        {
+            notify( app,
+                &tr!( "Failed to open the map picker.\nReason: {}", e ),
+                NotifyLevel::Error );
 
-    let addon_dir = parent.join( "svencoop_addon" ).join( "scripts/plugins" );
+            return;
+        }
+    };
+
+    let mut data = plugin_data.borrow_mut();
+    let Some( plugin ) = data.plugins.get_mut( &key ) else { return };
+    plugin.set_map_list( filter, &chosen );
+
+    let maps = plugin.map_list( filter ).into();
+    drop( data );
+
+    match filter
+    {
+        MapFilter::Included => app.set_txt_maps_included( maps ),
+        MapFilter::Excluded => app.set_txt_maps_excluded( maps )
+    }
+
+    on_detail_edited( app, plugin_data );
+}
+
+pub(crate) fn on_script_clicked(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    let Some( svencoop_path ) = SVENCOOP_PATH.get() else { return };
+    let addon_dir = addon_plugins_dir( &svencoop_path );
 
     if let Some( path ) = rfd::FileDialog::new()
         .set_directory( &addon_dir )
-        .add_filter( "Script Files", &["as"] )
+        .add_filter( tr!( "Script Files" ), &["as"] )
     .pick_file()
     {
         let path_str = path.to_string_lossy();
@@ -148,22 +562,97 @@ pub(crate) fn on_script_clicked(app: &AppWindow)
         .unwrap_or( &path_str );
 
         app.set_txt_script( relative.into() );
+        on_detail_edited( app, plugin_data );
     }
 }
 
-pub(crate) fn on_add_clicked(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+pub(crate) fn on_view_source_clicked(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    let Some( svencoop_path ) = SVENCOOP_PATH.get() else { return };
+    let data = plugin_data.borrow();
+    let Some( plugin ) = data.selected_plugin_name.as_ref().and_then( |k| data.plugins.get( k ) ) else { return };
+
+    let Some( path ) = resolve_script_path( &svencoop_path, &plugin.script ) else
+    {
+        notify( app, &tr!( "The script '{}' could not be found", plugin.script ), NotifyLevel::Error );
+        return;
+    };
+
+    drop( data );
+
+    if let Err( e ) = script_viewer::open_script_viewer( &path )
+    {
+        notify( app,
+            &tr!( "Failed to open the script viewer.\nReason: {}", e ),
+            NotifyLevel::Error );
+    }
+}
+
+// Actions from a plugin's right-click menu, on the plugin clicked
+pub(crate) fn on_row_menu(key: &str, action: i32, app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    if !plugin_data.borrow().selected.contains( key )
+    {
+        select_plugin( key, app, plugin_data );
+    }
+
+    if action == 2
+    {
+        on_view_source_clicked( app, plugin_data );
+        return;
+    }
+
+    let Some( svencoop_path ) = SVENCOOP_PATH.get() else { return };
+    let Some( script ) = plugin_data.borrow().plugins.get( key ).map( |p| p.script.clone() ) else { return };
+
+    let Some( path ) = resolve_script_path( &svencoop_path, &script ) else
+    {
+        notify( app, &tr!( "The script '{}' could not be found", script ), NotifyLevel::Error );
+        return;
+    };
+
+    let opened =
+    match action
+    {
+        0 => utils::open_in_editor( &config::read_store().unwrap_or_default().settings.editor_command, &path ),
+        _ => utils::reveal_in_file_manager( &path )
+    };
+
+    if let Err( e ) = opened
+    {
+        notify( app, &tr!( "Could not open '{}': {}", path.display(), e ), NotifyLevel::Error );
+    }
+}
+
+pub(crate) fn on_settings_clicked(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
 {
-    let parent = SVENCOOP_PATH
-        .get()
-        .expect( "SVENCOOP_PATH missing" )
-        .parent()
-        .expect( "missing parent" );
+    match settings::open_settings()
+    {
+        Ok( true ) =>
+        {   // The language may have changed, the list's counts and tooltips come from here
+            refresh_plugin_list( app, plugin_data );
+            theme::apply( app );
+            notify( app, &tr!( "Settings saved" ), NotifyLevel::Info );
+        }
+
+        Ok( false ) => {}
+        Err( e ) =>
+        {
+            notify( app,
+                &tr!( "Failed to open the settings.\nReason: {}", e ),
+                NotifyLevel::Error );
+        }
+    }
+}
 
-    let addon_dir = parent.join( "svencoop_addon" ).join( "scripts/plugins" );
+pub(crate) fn on_add_clicked(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    let Some( svencoop_path ) = SVENCOOP_PATH.get() else { return };
+    let addon_dir = addon_plugins_dir( &svencoop_path );
 
     if let Some( path ) = rfd::FileDialog::new()
         .set_directory( &addon_dir )
-        .add_filter( "Plugin scripts", &["as"] )
+        .add_filter( tr!( "Plugin scripts" ), &["as"] )
     .pick_file()
     {
         let name = path.file_stem()
@@ -173,11 +662,9 @@ pub(crate) fn on_add_clicked(app: &AppWindow, plugin_data: &Rc<RefCell<PluginCon
 
         if name.trim().is_empty()
         {
-            popup( "Invalid Plugin File",
-                "Failed to extract plugin name from the selected file.",
-                "❌",
-                PopupButtons::Ok,
-                |_| { } );
+            notify( app,
+                &tr!( "Failed to extract plugin name from the selected file." ),
+                NotifyLevel::Error );
 
             return;
         }
@@ -186,25 +673,21 @@ pub(crate) fn on_add_clicked(app: &AppWindow, plugin_data: &Rc<RefCell<PluginCon
 
         if data.plugins.contains_key( &name )
         {
-            popup( "Plugin Already Exists",
-                "A plugin with this name already exists. Please choose a different name.",
-                "❌",
-                PopupButtons::Ok,
-                |_| { } );
+            notify( app,
+                &tr!( "A plugin with this name already exists. Please choose a different name." ),
+                NotifyLevel::Error );
 
             return;
         }
 
         if let Err( e ) =
-            PluginEntry::install_plugin( &path.to_string_lossy(), &PathBuf::from( SVENCOOP_PATH.get().unwrap() ) )
+            PluginEntry::install_plugin( &path.to_string_lossy(), &svencoop_path )
         {
-            popup( "Install Error",
-                &format!(
+            notify( app,
+                &tr!(
                 "Failed to install plugin {}.\nReason:\n{}\n\n\
                 You will need to manually add this file to the game.", name, e ),
-                "❌",
-                PopupButtons::Ok,
-                |_| { } );
+                NotifyLevel::Warning );
 
             return;
         }
@@ -218,146 +701,700 @@ pub(crate) fn on_add_clicked(app: &AppWindow, plugin_data: &Rc<RefCell<PluginCon
         data.plugins.insert( key.clone(), plugin );
 
         drop( data );
-        refresh_plugin_list( app, plugin_data );
-
-        if let Err( e ) = save_plugins( &plugin_data.borrow() )
-        {
-            popup( "Save Error",
-                &format!( "Failed to save plugins.\nReason: {e}" ),
-                "❌",
-                PopupButtons::Ok,
-                |_| { } );
-        }
+        let _ = on_save_clicked( app, plugin_data );
     }
 }
 
+// Asks first, then removes every selected plugin
 pub(crate) fn on_remove_clicked(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
 {
-    let data = plugin_data.borrow();
+    let mut names: Vec<String> = plugin_data.borrow().selected.iter().cloned().collect();
 
-    let selected_name =
-    match data.selected_plugin_name.clone()
+    let message =
+    match names.len()
     {
-        Some( name ) => name,
-        None => return
+        0 => return,
+        1 => tr!( "Are you sure you want to remove the plugin '{}'?", names[0] ),
+        n =>
+        {
+            names.sort_unstable_by_key( |n| n.to_ascii_lowercase() );
+            trn!( "Are you sure you want to remove this {} plugin?\n{}", "Are you sure you want to remove these {} plugins?\n{}", n, n, names.join( "\n" ) )
+        }
     };
 
-    drop( data );
-    
-    let mut data = plugin_data.borrow_mut();
-    
-    if data.plugins.remove( &selected_name ).is_some()
-    {
-        data.selected_plugin_name = None;
-        drop( data );
-        refresh_plugin_list( app, plugin_data );
-        
-        app.set_txt_name( "".into() );
-        app.set_txt_script( "".into() );
-        app.set_txt_concommandns( "".into() );
-        app.set_cb_adminlevel( 0 );
-        app.set_txt_maps_included( "".into() );
-        app.set_txt_maps_excluded( "".into() );
-        app.set_chk_enabled( false );
-    }
+    let app_weak = app.as_weak();
+    let data_clone = plugin_data.clone();
+
+    popup( &tr!( "Confirm Remove" ),
+        &message,
+        "❓",
+        PopupButtons::YesNo,
+        move |choice|
+        {
+            let Some( app ) = app_weak.upgrade() else { return };
+
+            if choice != PopupChoice::Yes
+            {
+                return;
+            }
+
+            let mut data = data_clone.borrow_mut();
+
+            for name in &names
+            {
+                data.plugins.remove( name );
+            }
+
+            data.clear_selection();
+            drop( data );
+            refresh_plugin_list( &app, &data_clone );
+            show_plugin_details( &app, None );
+        });
 }
 
-pub(crate) fn on_apply_clicked(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+// Writes the detail fields to the selected plugin as they are typed in. A field that doesn't pass its check
+// shows why next to it and is left out, the plugin keeps its last good value for it until it is fixed.
+pub(crate) fn on_detail_edited(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
 {
-    let data = plugin_data.borrow();
+    let mut data = plugin_data.borrow_mut();
+    let Some( key ) = data.selected_plugin_name.clone() else { return };
 
-    let selected_name =
-    match data.selected_plugin_name.clone()
+    if !data.has_plugin( &key )
     {
-        Some( name ) => name,
-        None => return
-    };
-    
-    if !data.plugins.contains_key( &selected_name )
+        return;
+    }
+
+    let name = app.get_txt_name().trim().to_string();
+    let script = app.get_txt_script().trim().to_string();
+    let concommandns = app.get_txt_concommandns().trim().to_string();
+    let maps_included = app.get_txt_maps_included().to_string();
+    let maps_excluded = app.get_txt_maps_excluded().to_string();
+
+    let err_name = check_plugin_name( &name ).or_else( ||
     {
-        popup( "Plugin Not Found",
-            "Selected plugin could not be found in the internal store.",
-            "❌",
-            PopupButtons::Ok,
-            |_| { } );
+        ( name != key && data.has_plugin( &name ) ).then( || tr!( "There is already a plugin called '{}'", name ) )
+    });
+    let err_script = check_script_path( &script );
+    let err_concommandns = check_concommandns( &concommandns );
+    let err_maps_included = check_map_list( &maps_included );
+    let err_maps_excluded = check_map_list( &maps_excluded );
 
-        return;
+    let plugin = data.plugins.get_mut( &key ).expect( "The plugin should be selected at this point?" );
+    plugin.adminlevel = AdminLevel::from( app.get_cb_adminlevel() as isize );
+
+    if err_script.is_none()
+    {
+        plugin.script = script;
+    }
+
+    if err_concommandns.is_none()
+    {
+        plugin.concommandns = concommandns;
+    }
+
+    if err_maps_included.is_none()
+    {
+        plugin.maps_included = maps_included;
+    }
+
+    if err_maps_excluded.is_none()
+    {
+        plugin.maps_excluded = maps_excluded;
+    }
+
+    if err_name.is_none() && name != key
+    {
+        plugin.name = name.clone();
+        data.rename( &key, &name );
     }
 
     drop( data );
 
-    let mut plugins = plugin_data.borrow_mut();
-    let mut plugin = plugins.plugins.remove( &selected_name )
-        .expect( "The plugin should be selected at this point?" );
-    
-    plugin.name = app.get_txt_name().to_string();
-    plugin.script = app.get_txt_script().to_string();
-    plugin.concommandns = app.get_txt_concommandns().to_string();
-    plugin.adminlevel = AdminLevel::from( app.get_cb_adminlevel() as isize );
-    plugin.maps_included = app.get_txt_maps_included().to_string();
-    plugin.maps_excluded = app.get_txt_maps_excluded().to_string();
-    
-    let new_key = plugin.name.clone();
-    plugins.plugins.insert( new_key.clone(), plugin );
-    plugins.selected_plugin_name = Some( new_key );
-    
-    drop( plugins );
-    
+    app.set_err_name( err_name.unwrap_or_default().into() );
+    app.set_err_script( err_script.unwrap_or_default().into() );
+    app.set_err_concommandns( err_concommandns.unwrap_or_default().into() );
+    app.set_err_maps_included( err_maps_included.unwrap_or_default().into() );
+    app.set_err_maps_excluded( err_maps_excluded.unwrap_or_default().into() );
+
     refresh_plugin_list( app, plugin_data );
 }
 
+// Writes the plugin files, the window stays open and the result is shown in the status bar
 pub(crate) fn on_save_clicked(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>) -> Result<(), PlatformError>
 {
-    match save_plugins( &plugin_data.borrow() )
+    check_external_changes( app, plugin_data );
+
+    let saved = save_plugins( &plugin_data.borrow() );
+
+    match saved
     {
         Ok( missing_plugins ) =>
         {
-            match missing_plugins.is_empty()
+            if let Some( dir ) = SVENCOOP_PATH.get()
             {
-                true =>
-                {
-                    popup( "Saved",
-                          "All plugins are saved.",
-                          "ℹ️",
-                          PopupButtons::Ok,
-                          |_| { } )
-                }
+                plugin_data.borrow_mut().mark_saved( &dir );
+            }
 
-                false =>
-                {
-                    popup( "Saved",
-                          &format!( "Following plugins have missing script files:{}\n\n
-                          You will need to reinstall them.", missing_plugins ),
-                          "ℹ️",
-                          PopupButtons::Ok,
-                          |_| { } )
-                }
+            let count = plugin_data.borrow().plugins.values().filter( |p| p.state != PluginState::Removed ).count();
+            let missing: Vec<&str> = missing_plugins.lines().filter( |l| !l.is_empty() ).collect();
+
+            let saved = trn!( "Saved {} plugin", "Saved {} plugins", count );
+
+            let message =
+            match missing.is_empty()
+            {
+                true => saved,
+                false => tr!( "{}. Scripts not found: {}", saved, missing.join( ", " ) )
+            };
+
+            notify( app, &message, match missing.is_empty() { true => NotifyLevel::Info, false => NotifyLevel::Warning } );
+            refresh_plugin_list( app, plugin_data );
+
+            if let Some( install ) = config::read_store().ok().as_ref().and_then( current_install )
+            && install.rcon.reload_on_save
+            {
+                reload_server_plugins( app, install.rcon.clone() );
             }
+
+            Ok( () )
         }
 
         Err( e ) =>
         {
-            popup( "Error",
-                &format!( "Failed to save changes to plugin.\nReason: {}", e ),
+            popup( &tr!( "Error" ),
+                &tr!( "Failed to save changes to plugin.\nReason: {}", e ),
                 "❌",
                 PopupButtons::Ok,
                 |_| { } );
 
-            return Err( PlatformError::Other( e.to_string() ) );
+            notify( app, &tr!( "Plugins were not saved" ), NotifyLevel::Error );
+
+            Err( PlatformError::Other( e.to_string() ) )
         }
-    };
+    }
+}
+
+pub(crate) fn on_save_exit_clicked(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    if on_save_clicked( app, plugin_data ).is_ok()
+    {
+        let _ = app.hide();
+    }
+}
+// Closing with unsaved changes asks first: save them, throw them away, or stay open
+pub(crate) fn on_close_requested(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>) -> CloseRequestResponse
+{
+    if !plugin_data.borrow().has_unsaved_changes()
+    {
+        return CloseRequestResponse::HideWindow;
+    }
 
-    app.hide()
+    let choice = ask( &tr!( "Unsaved Changes" ),
+        &tr!( "There are changes which have not been saved to the plugin files.\nSave them before closing?" ),
+        "❓",
+        PopupButtons::SaveDiscardCancel );
+
+    match choice
+    {
+        PopupChoice::Save if on_save_clicked( app, plugin_data ).is_ok() => CloseRequestResponse::HideWindow,
+        PopupChoice::Discard => CloseRequestResponse::HideWindow,
+        _ => CloseRequestResponse::KeepWindowShown
+    }
 }
 
 pub(crate) fn on_enabled_toggled(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
 {
-    let mut plugin_data = plugin_data.borrow_mut();
+    let mut data = plugin_data.borrow_mut();
     
-    if let Some( ref name ) = plugin_data.selected_plugin_name.clone()
-    && let Some( plugin ) = plugin_data.plugins.get_mut( name )
+    if let Some( ref name ) = data.selected_plugin_name.clone()
+    && let Some( plugin ) = data.plugins.get_mut( name )
     {
         plugin.toggle_state();
-        app.set_plugin_list( ModelRc::new( VecModel::from( make_plugin_list( &plugin_data.plugins ) ) ) );
+    }
+
+    drop( data );
+    refresh_plugin_list( app, plugin_data );
+}
+
+// Called periodically and before saving. If the plugin files were changed by something else since we last read or wrote them,
+// the user decides whether to merge those changes in, reload from disk or keep their own version.
+pub(crate) fn check_external_changes(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    let Some( svencoop_dir ) = SVENCOOP_PATH.get() else { return };
+
+    // Skip this round if the data is busy, e.g. a popup is open while an edit is in progress
+    let changed = plugin_data.try_borrow_mut().is_ok_and( |mut data| data.disk.has_changed() );
+
+    if !changed
+    {
+        return;
+    }
+    // Take in the new state straight away so this doesn't fire again while the user is deciding
+    plugin_data.borrow_mut().disk = DiskSnapshot::capture( &svencoop_dir );
+
+    let theirs =
+    match read_plugin_files( &svencoop_dir )
+    {
+        Ok( plugins ) => plugins,
+        Err( e ) =>
+        {
+            notify( app,
+                &tr!( "The plugin files were changed outside {} but could not be read.\nReason:\n{}",
+                crate::APPNAME, e ),
+                NotifyLevel::Error );
+
+            return;
+        }
+    };
+
+    let app_weak = app.as_weak();
+    let data_clone = plugin_data.clone();
+
+    popup( &tr!( "Plugin Files Changed" ),
+        &tr!( "The plugin files were modified outside of this application.\n\n\
+        Yes - merge those changes with yours\n\
+        No - reload from disk, discarding your changes\n\
+        Cancel - keep your version, saving will overwrite theirs" ),
+        "⚠️",
+        PopupButtons::YesNoCancel,
+        move |choice|
+        {
+            let Some( app ) = app_weak.upgrade() else { return };
+            let mut data = data_clone.borrow_mut();
+
+            let conflicts =
+            match choice
+            {
+                PopupChoice::Yes =>
+                {
+                    let ( merged, conflicts ) = merge_plugins( &data.baseline, &data.plugins, &theirs );
+                    data.plugins = merged;
+
+                    conflicts
+                }
+
+                PopupChoice::No =>
+                {
+                    data.plugins = theirs.clone();
+                    Vec::new()
+                }
+
+                _ => return
+            };
+
+            data.baseline = theirs.clone();
+
+            data.prune_selection();
+
+            let selected = data.selected_plugin_name.as_ref().and_then( |n| data.plugins.get( n ) );
+            show_plugin_details( &app, selected );
+            drop( data );
+            refresh_plugin_list( &app, &data_clone );
+
+            if !conflicts.is_empty()
+            {
+                notify( &app,
+                    &tr!( "These plugins were changed on both sides, your version was kept:\n{}",
+                    conflicts.join( "\n" ) ),
+                    NotifyLevel::Info );
+            }
+        });
+}
+
+// The stored install being managed, None when it was given with --game-dir and not saved
+fn current_install(store: &config::Config) -> Option<&Install>
+{
+    let active = SVENCOOP_PATH.get();
+    store.installs.iter().find( |i| Some( &i.path ) == active.as_ref() )
+}
+// Fills the install switcher from the config, with the install being managed selected
+pub(crate) fn refresh_install_list(app: &AppWindow)
+{
+    let store = config::read_store().unwrap_or_default();
+    let active = SVENCOOP_PATH.get();
+    let mut names: Vec<SharedString> = store.installs.iter().map( |i| i.name.as_str().into() ).collect();
+
+    let index =
+    match store.installs.iter().position( |i| Some( &i.path ) == active.as_ref() )
+    {
+        Some( i ) => i,
+        None =>
+        {   // Given with --game-dir and not stored, still show it as the current one
+            let name = active.as_deref().map( Install::default_name ).unwrap_or_default();
+            names.push( tr!( "{} (not saved)", name ).into() );
+            names.len() - 1
+        }
+    };
+
+    app.set_install_name( names.get( index ).cloned().unwrap_or_default() );
+    app.set_install_names( ModelRc::new( VecModel::from( names ) ) );
+    app.set_install_index( index as i32 );
+}
+// Loads the plugins of another install in place of the current ones
+fn switch_install(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>, install: &Install)
+{
+    if !config::is_game_dir( &install.path )
+    {
+        notify( app,
+            &tr!( "The install '{}' could not be found at '{}'. It may have been moved or uninstalled.\n\
+            Use 'Add install...' to add it again from where it is now.",
+            install.name, install.path.display() ),
+            NotifyLevel::Error );
+
+        refresh_install_list( app );
+        return;
+    }
+
+    let plugins =
+    match config::ensure_plugin_files( &install.path ).and_then( |_| read_plugin_files( &install.path ) )
+    {
+        Ok( plugins ) => plugins,
+        Err( e ) =>
+        {
+            notify( app,
+                &tr!( "The plugin file in '{}' could not be opened.\nReason:\n{}",
+                install.path.display(), e ),
+                NotifyLevel::Error );
+
+            refresh_install_list( app );
+            return;
+        }
+    };
+
+    save_view_prefs( app, plugin_data );
+    SVENCOOP_PATH.set( install.path.clone() );
+
+    let mut ctx = PluginContext::from_hashmap( plugins );
+    ctx.disk = DiskSnapshot::capture( &install.path );
+    ctx.health = plugin_data.borrow().health.clone();
+    ctx.health.reset();
+    *plugin_data.borrow_mut() = ctx;
+
+    if let Err( e ) = config::set_active_install( &install.name )
+    {
+        eprintln!( "Failed to remember the active install: {}", e );
+    }
+
+    restore_view_prefs( app, plugin_data );
+    refresh_install_list( app );
+}
+// Changes made to the current install would be lost by switching away from it, so check first
+fn confirm_leave_install(plugin_data: &Rc<RefCell<PluginContext>>) -> bool
+{
+    if !plugin_data.borrow().has_unsaved_changes()
+    {
+        return true;
+    }
+
+    ask( &tr!( "Unsaved Changes" ),
+        &tr!( "The current install has unsaved changes which will be lost.\nSwitch installs anyway?" ),
+        "❓",
+        PopupButtons::YesNo ) == PopupChoice::Yes
+}
+
+pub(crate) fn on_install_selected(index: i32, app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    let store = config::read_store().unwrap_or_default();
+
+    let Some( install ) = store.installs.get( index as usize ) else { return };
+
+    if Some( &install.path ) == SVENCOOP_PATH.get().as_ref()
+    {
+        return;
+    }
+
+    match confirm_leave_install( plugin_data )
+    {
+        true => switch_install( app, plugin_data, install ),
+        false => refresh_install_list( app )// puts the switcher back
+    }
+}
+
+pub(crate) fn on_add_install_clicked(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    let Some( folder ) = rfd::FileDialog::new()
+        .set_title( tr!( "Select the Sven Co-op \"svencoop\" folder" ) )
+    .pick_folder() else { return };
+    let Some( svencoop_dir ) = config::resolve_svencoop_dir( &folder ) else
+    {
+        notify( app,
+            &tr!( "'{}' does not look like a Sven Co-op install.\n\
+            Select the 'svencoop' folder containing '{}'.",
+            folder.display(), crate::plugin::FILENAME_PLUGINS ),
+            NotifyLevel::Error );
+
+        return;
+    };
+
+    let mut store =
+    match config::read_store()
+    {
+        Ok( store ) => store,
+        Err( e ) =>
+        {
+            notify( app,
+                &tr!( "Failed to save the install list.\nReason: {}", e ),
+                NotifyLevel::Error );
+
+            return;
+        }
+    };
+
+    if let Some( existing ) = store.installs.iter().find( |i| i.path == svencoop_dir )
+    {
+        notify( app,
+            &tr!( "This install is already in the list as '{}'.", existing.name ),
+            NotifyLevel::Info );
+
+        return;
+    }
+
+    let install = store.add_install( &Install::default_name( &svencoop_dir ), &svencoop_dir );
+
+    if let Err( e ) = config::write_store( &store )
+    {
+        notify( app,
+            &tr!( "Failed to save the install list.\nReason: {}", e ),
+            NotifyLevel::Error );
+
+        return;
+    }
+
+    match confirm_leave_install( plugin_data )
+    {
+        true => switch_install( app, plugin_data, &install ),
+        false => refresh_install_list( app )
+    }
+}
+
+pub(crate) fn on_server_clicked(app: &AppWindow)
+{
+    let store = config::read_store().unwrap_or_default();
+
+    let Some( install ) = current_install( &store ) else
+    {
+        notify( app,
+            &tr!( "This install isn't saved, add it with 'Add install...' to set up its server." ),
+            NotifyLevel::Warning );
+
+        return;
+    };
+
+    match rcon_settings::open_rcon_settings( &install.name )
+    {
+        Ok( true ) => notify( app, &tr!( "Server settings saved" ), NotifyLevel::Info ),
+        Ok( false ) => {}
+        Err( e ) =>
+        {
+            notify( app,
+                &tr!( "Failed to open the server settings.\nReason: {}", e ),
+                NotifyLevel::Error );
+        }
+    }
+}
+// Plugins installed from the catalogue are added to the list and saved, the same as one added from a file
+pub(crate) fn on_catalogue_clicked(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    let Some( svencoop_path ) = SVENCOOP_PATH.get() else { return };
+    let known = plugin_data.borrow().plugins.values().map( |p| p.name.to_lowercase() ).collect();
+
+    let installed =
+    match catalogue_browser::browse_catalogue( &svencoop_path, known )
+    {
+        Ok( installed ) => installed,
+        Err( e ) =>
+        {
+            notify( app,
+                &tr!( "Failed to open the catalogue.\nReason: {}", e ),
+                NotifyLevel::Error );
+
+            return;
+        }
+    };
+
+    if installed.is_empty()
+    {
+        return;
+    }
+
+    let mut registered = Vec::new();
+    let mut skipped = Vec::new();
+
+    for ( name, script ) in &installed
+    {   // An entry the user is removing anyway is replaced without asking, any other is theirs to keep
+        let taken = plugin_data.borrow().plugins.get( name ).is_some_and( |p| p.state != PluginState::Removed );
+
+        if taken && ask( &tr!( "Plugin Already Listed" ),
+            &tr!( "A plugin called '{}' is already in the list.
+Replace its entry with the one from the catalogue? Its settings will be reset.", name ),
+            "⚠️",
+            PopupButtons::YesNo ) != PopupChoice::Yes
+        {
+            skipped.push( name.as_str() );
+            continue;
+        }
+
+        let ( key, plugin ) = PluginEntry::add_plugin( name, script );
+        plugin_data.borrow_mut().plugins.insert( key, plugin );
+        registered.push( name.as_str() );
+    }
+
+    if !registered.is_empty() && on_save_clicked( app, plugin_data ).is_err()
+    {
+        return;
+    }
+
+    let mut message = Vec::new();
+
+    if !registered.is_empty()
+    {
+        message.push( tr!( "Installed from the catalogue: {}", registered.join( ", " ) ) );
+    }
+
+    if !skipped.is_empty()
+    {
+        message.push( tr!( "The files of {} were installed, but the plugin list was left as it was", skipped.join( ", " ) ) );
+    }
+
+    notify( app, &message.join( "\n" ), match skipped.is_empty() { true => NotifyLevel::Info, false => NotifyLevel::Warning } );
+}
+// Sends as_reloadplugins over RCON on another thread, the server's reply is shown once it comes back
+pub(crate) fn reload_server_plugins(app: &AppWindow, settings: RconSettings)
+{
+    let app_weak = app.as_weak();
+
+    thread::spawn( move ||
+    {
+        let result = rcon::send_command( &settings, rcon::RELOAD_COMMAND );
+
+        let _ = app_weak.upgrade_in_event_loop( move |app|
+        {
+            match result
+            {
+                Ok( reply ) if reply.is_empty() => notify( &app, &tr!( "Plugins reloaded on the server" ), NotifyLevel::Info ),
+                Ok( reply ) => notify( &app, &tr!( "Plugins reloaded on the server:\n{}", reply ), NotifyLevel::Info ),
+                Err( e ) =>
+                {
+                    notify( &app,
+                        &tr!( "Could not reload plugins on the server.\nReason: {}", e ),
+                        NotifyLevel::Error );
+                }
+            }
+        });
+    });
+}
+
+pub(crate) fn on_reload_server_clicked(app: &AppWindow)
+{
+    match config::read_store().ok().as_ref().and_then( current_install )
+    {
+        Some( install ) if !install.rcon.password.is_empty() => reload_server_plugins( app, install.rcon.clone() ),
+        _ => notify( app, &tr!( "RCON isn't set up for this install, use 'Server...' to set it up." ), NotifyLevel::Warning )
+    }
+}
+
+// Puts the window back where it was last time, before it is shown
+pub(crate) fn restore_window_prefs(app: &AppWindow)
+{
+    let prefs = config::read_store().unwrap_or_default().window;
+
+    if let ( Some( width ), Some( height ) ) = ( prefs.width, prefs.height )
+    {
+        app.window().set_size( PhysicalSize::new( width, height ) );
+    }
+
+    if let ( Some( x ), Some( y ) ) = ( prefs.x, prefs.y )
+    {
+        app.window().set_position( PhysicalPosition::new( x, y ) );
+    }
+
+    if let Some( width ) = prefs.name_column_width
+    {
+        app.set_name_column_width( width );
+    }
+}
+// Sorting, filter and selection as the current install was last left
+pub(crate) fn restore_view_prefs(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    let store = config::read_store().unwrap_or_default();
+    let active = SVENCOOP_PATH.get();
+    let view = store.installs
+        .iter()
+        .find( |i| Some( &i.path ) == active.as_ref() )
+        .map( |i| i.view.clone() )
+    .unwrap_or_default();
+
+    app.set_sort_column( sort_column_to_ui( view.sort_column ) );
+    app.set_sort_descending( view.sort_descending );
+    app.set_filter_text( view.filter.into() );
+    app.set_filter_enabled( view.show_enabled );
+    app.set_filter_disabled( view.show_disabled );
+    app.set_filter_missing_script( view.show_missing_script );
+    app.set_filter_map_restricted( view.show_map_restricted );
+    refresh_plugin_list( app, plugin_data );
+
+    let last = view.last_plugin.filter( |name| plugin_data.borrow().has_plugin( name ) );
+
+    match last
+    {
+        Some( name ) => select_plugin( &name, app, plugin_data ),
+        None => on_clear_selection( app, plugin_data )
+    }
+}
+
+fn store_view_prefs(store: &mut config::Config, app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    let active = SVENCOOP_PATH.get();
+
+    if let Some( install ) = store.installs.iter_mut().find( |i| Some( &i.path ) == active.as_ref() )
+    {
+        install.view = config::ViewPrefs
+        {
+            last_plugin: plugin_data.borrow().selected_plugin_name.clone(),
+            sort_column: sort_column_from_ui( app.get_sort_column() ),
+            sort_descending: app.get_sort_descending(),
+            filter: app.get_filter_text().to_string(),
+            show_enabled: app.get_filter_enabled(),
+            show_disabled: app.get_filter_disabled(),
+            show_missing_script: app.get_filter_missing_script(),
+            show_map_restricted: app.get_filter_map_restricted(),
+            extra: std::mem::take( &mut install.view.extra )
+        };
+    }
+}
+// Remembers how the current install's list was left, before switching away from it
+pub(crate) fn save_view_prefs(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    let Ok( mut store ) = config::read_store() else { return };
+    store_view_prefs( &mut store, app, plugin_data );
+
+    if let Err( e ) = config::write_store( &store )
+    {
+        eprintln!( "Failed to save preferences: {}", e );
+    }
+}
+// Called when the app closes, saves the window layout along with the current install's list view
+pub(crate) fn save_ui_prefs(app: &AppWindow, plugin_data: &Rc<RefCell<PluginContext>>)
+{
+    let Ok( mut store ) = config::read_store() else { return };
+    store_view_prefs( &mut store, app, plugin_data );
+
+    let window = app.window();
+
+    if !window.is_minimized() && !window.is_maximized()
+    {
+        let ( size, position ) = ( window.size(), window.position() );
+
+        store.window.width = Some( size.width );
+        store.window.height = Some( size.height );
+        store.window.x = Some( position.x );
+        store.window.y = Some( position.y );
+    }
+
+    store.window.name_column_width = Some( app.get_name_column_width() );
+
+    if let Err( e ) = config::write_store( &store )
+    {
+        eprintln!( "Failed to save preferences: {}", e );
     }
 }
@@ -0,0 +1,226 @@
+/*
+	Sven Co-op Plugin Manager Version 2.0
+
+Copyright (C) 2025 Outerbeast
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program. If not, see <https://www.gnu.org/licenses/>.
+*/
+use std::
+{
+    collections::{ BTreeMap, HashSet },
+    path::Path,
+    rc::Rc
+};
+
+use slint::
+{
+    CloseRequestResponse,
+    ComponentHandle,
+    Model,
+    ModelRc,
+    PlatformError,
+    VecModel
+};
+
+use crate::
+{
+    alloc_shared,
+    maps,
+    trn,
+    utils,
+    MapRow
+};
+
+use super::theme;
+
+pub type MapPickerWindow = crate::MapPicker;
+
+struct PickerState
+{
+    maps: Vec<String>,// installed maps plus any already in the list that aren't
+    installed: HashSet<String>,
+    cycle: HashSet<String>,
+    chosen: HashSet<String>// lowercase
+}
+
+impl PickerState
+{   // Maps grouped by series, only series with something matching the filter are listed
+    fn rows(&self, filter: &str) -> Vec<MapRow>
+    {
+        let mut series: BTreeMap<&str, Vec<&String>> = BTreeMap::new();
+
+        for map in &self.maps
+        {
+            let prefix = maps::series_prefix( map );
+
+            if filter.split_whitespace().all( |word| utils::fuzzy_match( word, map ) || utils::fuzzy_match( word, prefix ) )
+            {
+                series.entry( prefix ).or_default().push( map );
+            }
+        }
+
+        let mut rows = Vec::new();
+
+        for ( prefix, list ) in series
+        {
+            rows.push( MapRow
+            {
+                name: prefix.into(),
+                header: true,
+                count: list.len() as i32,
+                checked: list.iter().all( |m| self.chosen.contains( *m ) ),
+                in_cycle: list.iter().any( |m| self.cycle.contains( *m ) ),
+                installed: true
+            });
+
+            rows.extend( list.into_iter().map( |map| MapRow
+            {
+                name: map.as_str().into(),
+                header: false,
+                count: 0,
+                checked: self.chosen.contains( map ),
+                in_cycle: self.cycle.contains( map ),
+                installed: self.installed.contains( map )
+            }));
+        }
+
+        rows
+    }
+}
+// Shows the map picker for one of a plugin's map lists, starting with the maps in `current` ticked.
+// Returns the maps ticked when OK is clicked, in the order they were in with new ones added at the end.
+pub fn pick_maps(svencoop_dir: &Path, heading: &str, current: &[String]) -> Result<Option<Vec<String>>, PlatformError>
+{
+    let picker = MapPickerWindow::new()?;
+    theme::apply( &picker );
+    let installed = maps::find_maps( svencoop_dir );
+    let chosen: HashSet<String> = current.iter().map( |m| m.to_ascii_lowercase() ).collect();
+
+    let mut all = installed.clone();
+    all.extend( chosen.iter().filter( |m| installed.binary_search( m ).is_err() ).cloned() );
+    all.sort_unstable();
+
+    let state = alloc_shared!( PickerState
+    {
+        maps: all,
+        installed: installed.into_iter().collect(),
+        cycle: maps::read_mapcycle( svencoop_dir ),
+        chosen
+    });
+
+    let rows = Rc::new( VecModel::from( state.borrow().rows( "" ) ) );
+    let accepted = alloc_shared!( false );
+
+    picker.set_heading( heading.into() );
+    picker.set_rows( ModelRc::from( rows.clone() ) );
+    picker.set_summary( trn!( "{} map selected", "{} maps selected", state.borrow().chosen.len() ).into() );
+
+    let picker_weak = picker.as_weak();
+    let state_clone = state.clone();
+    let rows_clone = rows.clone();
+    picker.on_filter_changed( move ||
+    {
+        if let Some( p ) = picker_weak.upgrade()
+        {
+            rows_clone.set_vec( state_clone.borrow().rows( &p.get_filter_text() ) );
+        }
+    });
+
+    let picker_weak = picker.as_weak();
+    let state_clone = state.clone();
+    let rows_clone = rows.clone();
+    picker.on_row_toggled( move |i|
+    {
+        let Some( p ) = picker_weak.upgrade() else { return };
+        let Some( row ) = rows_clone.row_data( i as usize ) else { return };
+        let mut state = state_clone.borrow_mut();
+
+        match row.header
+        {   // A heading ticks every map of its series shown, or unticks them if they all were
+            true =>
+            {
+                let series: Vec<String> = rows_clone
+                    .iter()
+                    .filter( |r| !r.header && maps::series_prefix( &r.name ) == row.name.as_str() )
+                    .map( |r| r.name.to_string() )
+                .collect();
+
+                for map in series
+                {
+                    match row.checked
+                    {
+                        true => state.chosen.remove( &map ),
+                        false => state.chosen.insert( map )
+                    };
+                }
+            }
+
+            false =>
+            {
+                let map = row.name.to_string();
+
+                if !state.chosen.remove( &map )
+                {
+                    state.chosen.insert( map );
+                }
+            }
+        }
+
+        rows_clone.set_vec( state.rows( &p.get_filter_text() ) );
+        p.set_summary( trn!( "{} map selected", "{} maps selected", state.chosen.len() ).into() );
+    });
+
+    let picker_weak = picker.as_weak();
+    let accepted_clone = accepted.clone();
+    picker.on_accept( move ||
+    {
+        *accepted_clone.borrow_mut() = true;
+
+        if let Some( p ) = picker_weak.upgrade()
+        {
+            let _ = p.hide();
+        }
+    });
+
+    let picker_weak = picker.as_weak();
+    picker.on_cancel( move ||
+    {
+        if let Some( p ) = picker_weak.upgrade()
+        {
+            let _ = p.hide();
+        }
+    });
+
+    picker.window().on_close_requested( || CloseRequestResponse::HideWindow );
+    picker.run()?;
+
+    if !accepted.take()
+    {
+        return Ok( None );
+    }
+
+    let chosen = &state.borrow().chosen;
+    // Keep the maps that stay as they were written, then add the new ones
+    let mut result: Vec<String> = current
+        .iter()
+        .filter( |m| chosen.contains( &m.to_ascii_lowercase() ) )
+        .cloned()
+    .collect();
+
+    let kept: HashSet<String> = result.iter().map( |m| m.to_ascii_lowercase() ).collect();
+    let mut added: Vec<String> = chosen.iter().filter( |m| !kept.contains( *m ) ).cloned().collect();
+    added.sort_unstable();
+    result.extend( added );
+
+    Ok( Some( result ) )
+}
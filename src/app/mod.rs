@@ -15,18 +15,37 @@ GNU General Public License for more details.
 You should have received a copy of the GNU General Public License
 along with this program. If not, see <https://www.gnu.org/licenses/>.
 */
+pub mod catalogue_browser;
+pub mod commands;
 pub mod controller;
+pub mod map_picker;
+pub mod rcon_settings;
+pub mod script_viewer;
+pub mod settings;
+pub mod theme;
+pub mod wizard;
+
+use std::time::Duration;
 
 use slint::
 {
+    CloseRequestResponse,
     ComponentHandle,
-    PlatformError
+    Model,
+    ModelRc,
+    PlatformError,
+    SharedString,
+    Timer,
+    TimerMode,
+    VecModel
 };
 
 use crate::
 {
     alloc_shared,
-    plugin::PluginContext
+    i18n,
+    plugin::PluginContext,
+    Notification
 };
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -38,75 +57,328 @@ pub enum PopupButtons
     OkCancel,
     YesNo,
     YesNoCancel,
-    RetryCancel
+    RetryCancel,
+    SaveDiscardCancel
 }
 
+// Matches the result each MessageDialog button closes with, None is the dialog being closed without a choice
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(i32)]
 pub enum PopupChoice
 {
-    None,
+    None = 0,
     Ok,
     Cancel,
     Yes,
     No,
     Retry,
+    Save,
+    Discard
+}
+
+impl From<i32> for PopupChoice
+{
+    fn from(value: i32) -> Self
+    {
+        match value
+        {
+            1 => PopupChoice::Ok,
+            2 => PopupChoice::Cancel,
+            3 => PopupChoice::Yes,
+            4 => PopupChoice::No,
+            5 => PopupChoice::Retry,
+            6 => PopupChoice::Save,
+            7 => PopupChoice::Discard,
+            _ => PopupChoice::None
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(i32)]
+pub enum NotifyLevel
+{
+    Info = 0,
+    Warning,
+    Error
 }
 
+const TOAST_TIMEOUT: Duration = Duration::from_secs( 5 );
+
 pub type AppWindow = super::PluginManagerWindow;
 
-pub fn popup<F>(title: &str, message: &str, icon: &str, buttons: PopupButtons, on_close: F)
-where F: Fn(PopupChoice) + 'static
+// Shows a dialog and waits for it to be closed, returning the button that was clicked.
+// For questions that need an answer before going on, anything else should use notify.
+pub fn ask(title: &str, message: &str, icon: &str, buttons: PopupButtons) -> PopupChoice
 {
-    let dialog = super::MessageDialog::new().expect( "Dialog creation failed" );
+    let dialog =
+    match super::MessageDialog::new()
+    {
+        Ok( dialog ) =>
+        {   // This may be the first window, before the UI language could be set
+            i18n::apply_to_ui();
+            theme::apply( &dialog );
+            dialog
+        }
+
+        Err( e ) =>
+        {
+            eprintln!( "Dialog creation failed: {}", e );
+            return PopupChoice::None;
+        }
+    };
+
     dialog.set_message_title( title.into() );
     dialog.set_message_text( message.into() );
     dialog.set_icon_text( icon.into() );
     dialog.set_buttons( buttons as i32 );
-    // Set what happens when closing
+
+    let choice = alloc_shared!( PopupChoice::None );
+    let choice_clone = choice.clone();
     let dialog_weak = dialog.as_weak();
     dialog.on_close_dialog( move |r|
     {
+        *choice_clone.borrow_mut() = PopupChoice::from( r );
+
         if let Some( d ) = dialog_weak.upgrade()
         {
-            let choice = match r
-            {
-                1 => PopupChoice::Yes,
-                2 => PopupChoice::No,
-                _ => PopupChoice::None,
-            };
-            
-            on_close( choice );
             d.hide().ok();
         }
     });
 
     let _ = dialog.run();
+
+    *choice.borrow()
+}
+// Same as ask, with the answer handed to on_close once the dialog has closed
+pub fn popup<F>(title: &str, message: &str, icon: &str, buttons: PopupButtons, on_close: F)
+where F: FnOnce(PopupChoice)
+{
+    on_close( ask( title, message, icon, buttons ) );
+}
+// A message that doesn't need an answer: it shows in the status bar and as a toast that goes away on its own,
+// and is kept in the notification log
+pub fn notify(app: &AppWindow, message: &str, level: NotifyLevel)
+{
+    let message = SharedString::from( message );
+
+    app.set_status_text( message.clone() );
+    app.set_status_error( level == NotifyLevel::Error );
+    app.set_toast_text( message.clone() );
+    app.set_toast_level( level as i32 );
+    app.set_toast_visible( true );
+
+    if !app.get_log_visible()
+    {
+        app.set_unread_count( app.get_unread_count() + 1 );
+    }
+
+    let log = app.get_notifications();
+
+    match log.as_any().downcast_ref::<VecModel<Notification>>()
+    {
+        Some( model ) => model.insert( 0, Notification { message: message.clone(), level: level as i32 } ),
+        None => app.set_notifications( ModelRc::new( VecModel::from( vec![Notification { message: message.clone(), level: level as i32 }] ) ) )
+    }
+    // Hide it later, unless another message has taken its place
+    let app_weak = app.as_weak();
+    Timer::single_shot( TOAST_TIMEOUT, move ||
+    {
+        if let Some( app ) = app_weak.upgrade() && app.get_toast_text() == message
+        {
+            app.set_toast_visible( false );
+            app.set_status_text( SharedString::new() );
+        }
+    });
 }
 
 pub fn launch_gui(ctx: PluginContext) -> Result<(), PlatformError>
 {
     let plugin_data = alloc_shared!( ctx );
     let app = super::PluginManagerWindow::new()?;
+    i18n::apply_to_ui();
+    theme::apply( &app );
+    controller::restore_window_prefs( &app );
     app.show()?;
     
-    controller::refresh_plugin_list( &app, &plugin_data );
+    controller::restore_view_prefs( &app, &plugin_data );
+    controller::refresh_install_list( &app );
 
     let app_weak = app.as_weak();
     let plugin_data_cloned = plugin_data.clone();
-    app.on_plugin_selected( move |i|
+    app.on_row_clicked( move |key, shift, ctrl|
+    {
+        if let Some( handle ) = app_weak.upgrade()
+        {
+            controller::on_row_clicked( &key, shift, ctrl, &handle, &plugin_data_cloned );
+        }
+    });
+
+    let app_weak = app.as_weak();
+    let gui_data_clone = plugin_data.clone();
+    app.on_row_menu( move |key, action|
+    {
+        if let Some( handle ) = app_weak.upgrade()
+        {
+            controller::on_row_menu( &key, action, &handle, &gui_data_clone );
+        }
+    });
+
+    let app_weak = app.as_weak();
+    let gui_data_clone = plugin_data.clone();
+    app.on_move_selection( move |delta, shift|
+    {
+        if let Some( handle ) = app_weak.upgrade()
+        {
+            controller::on_move_selection( delta, shift, &handle, &gui_data_clone );
+        }
+    });
+
+    let app_weak = app.as_weak();
+    let gui_data_clone = plugin_data.clone();
+    app.on_run_command( move |command|
+    {
+        if let Some( handle ) = app_weak.upgrade()
+        {
+            commands::run_command( command, &handle, &gui_data_clone );
+        }
+    });
+
+    let app_weak = app.as_weak();
+    app.on_palette_filter_changed( move ||
+    {
+        if let Some( handle ) = app_weak.upgrade()
+        {
+            commands::on_palette_filter_changed( &handle );
+        }
+    });
+
+    let app_weak = app.as_weak();
+    app.on_clear_notifications( move ||
+    {
+        if let Some( handle ) = app_weak.upgrade()
+        {
+            handle.set_notifications( ModelRc::new( VecModel::<Notification>::default() ) );
+            handle.set_unread_count( 0 );
+        }
+    });
+
+    let app_weak = app.as_weak();
+    app.on_server_clicked( move ||
+    {
+        if let Some( handle ) = app_weak.upgrade()
+        {
+            controller::on_server_clicked( &handle );
+        }
+    });
+
+    let app_weak = app.as_weak();
+    let gui_data_clone = plugin_data.clone();
+    app.on_catalogue_clicked( move ||
+    {
+        if let Some( handle ) = app_weak.upgrade()
+        {
+            controller::on_catalogue_clicked( &handle, &gui_data_clone );
+        }
+    });
+
+    let app_weak = app.as_weak();
+    let gui_data_clone = plugin_data.clone();
+    app.on_settings_clicked( move ||
+    {
+        if let Some( handle ) = app_weak.upgrade()
+        {
+            controller::on_settings_clicked( &handle, &gui_data_clone );
+        }
+    });
+
+    let app_weak = app.as_weak();
+    let gui_data_clone = plugin_data.clone();
+    app.on_select_all( move ||
+    {
+        if let Some( handle ) = app_weak.upgrade()
+        {
+            controller::on_select_all( &handle, &gui_data_clone );
+        }
+    });
+
+    let app_weak = app.as_weak();
+    let gui_data_clone = plugin_data.clone();
+    app.on_clear_selection( move ||
+    {
+        if let Some( handle ) = app_weak.upgrade()
+        {
+            controller::on_clear_selection( &handle, &gui_data_clone );
+        }
+    });
+
+    let app_weak = app.as_weak();
+    let gui_data_clone = plugin_data.clone();
+    app.on_bulk_set_enabled( move |enabled|
+    {
+        if let Some( handle ) = app_weak.upgrade()
+        {
+            controller::on_bulk_set_enabled( enabled, &handle, &gui_data_clone );
+        }
+    });
+
+    let app_weak = app.as_weak();
+    let gui_data_clone = plugin_data.clone();
+    app.on_bulk_set_adminlevel( move |level|
+    {
+        if let Some( handle ) = app_weak.upgrade()
+        {
+            controller::on_bulk_set_adminlevel( level, &handle, &gui_data_clone );
+        }
+    });
+
+    let app_weak = app.as_weak();
+    let gui_data_clone = plugin_data.clone();
+    app.on_bulk_edit_map( move |maps, list, add|
+    {
+        if let Some( handle ) = app_weak.upgrade()
+        {
+            controller::on_bulk_edit_map( &maps, list, add, &handle, &gui_data_clone );
+        }
+    });
+
+    let app_weak = app.as_weak();
+    let gui_data_clone = plugin_data.clone();
+    app.on_list_view_changed( move ||
+    {
+        if let Some( handle ) = app_weak.upgrade()
+        {
+            controller::refresh_plugin_list( &handle, &gui_data_clone );
+        }
+    });
+
+    let app_weak = app.as_weak();
+    let gui_data_clone = plugin_data.clone();
+    app.on_pick_maps( move |list|
     {
         if let Some( handle ) = app_weak.upgrade()
         {
-            controller::on_plugin_selected( i, &handle, &plugin_data_cloned );
+            controller::on_pick_maps( list, &handle, &gui_data_clone );
         }
     });
 
     let app_weak = app.as_weak();
+    let gui_data_clone = plugin_data.clone();
+    app.on_view_source_clicked( move ||
+    {
+        if let Some( handle ) = app_weak.upgrade()
+        {
+            controller::on_view_source_clicked( &handle, &gui_data_clone );
+        }
+    });
+
+    let app_weak = app.as_weak();
+    let gui_data_clone = plugin_data.clone();
     app.on_script_clicked( move ||
     {
         if let Some( handle ) = app_weak.upgrade()
         {
-            controller::on_script_clicked( &handle );
+            controller::on_script_clicked( &handle, &gui_data_clone );
         }
     });
     
@@ -126,30 +398,17 @@ pub fn launch_gui(ctx: PluginContext) -> Result<(), PlatformError>
     {
         if let Some( handle ) = app_weak.upgrade()
         {
-            let name = gui_data_clone.borrow().selected_plugin_name.clone().unwrap_or_default();
-            let data_clone = gui_data_clone.clone();
-            // Ask first before removal
-            popup( "Confirm Remove",
-                &format!( "Are you sure you want to remove the plugin '{}'?", name ),
-                "❓",
-                PopupButtons::YesNo,
-                move |choice|
-                {
-                    if choice == PopupChoice::Yes
-                    {
-                        controller::on_remove_clicked( &handle, &data_clone );
-                    }
-                });
+            controller::on_remove_clicked( &handle, &gui_data_clone );
         }
     });
 
     let app_weak = app.as_weak();
     let gui_data_clone = plugin_data.clone();
-    app.on_apply_clicked( move ||
+    app.on_detail_edited( move ||
     {
         if let Some( handle ) = app_weak.upgrade()
         {
-            controller::on_apply_clicked( &handle, &gui_data_clone );
+            controller::on_detail_edited( &handle, &gui_data_clone );
         }
     });
     
@@ -163,6 +422,16 @@ pub fn launch_gui(ctx: PluginContext) -> Result<(), PlatformError>
         }
     });
 
+    let app_weak = app.as_weak();
+    let gui_data_clone = plugin_data.clone();
+    app.on_save_exit_clicked( move ||
+    {
+        if let Some( handle ) = app_weak.upgrade()
+        {
+            controller::on_save_exit_clicked( &handle, &gui_data_clone );
+        }
+    });
+
     let app_weak = app.as_weak();
     let gui_data_clone = plugin_data.clone();
     app.on_enabled_toggled( move |_|
@@ -173,5 +442,61 @@ pub fn launch_gui(ctx: PluginContext) -> Result<(), PlatformError>
         }
     });
 
-    app.run()
+    let app_weak = app.as_weak();
+    let gui_data_clone = plugin_data.clone();
+    app.on_install_selected( move |i|
+    {
+        if let Some( handle ) = app_weak.upgrade()
+        {
+            controller::on_install_selected( i, &handle, &gui_data_clone );
+        }
+    });
+
+    let app_weak = app.as_weak();
+    let gui_data_clone = plugin_data.clone();
+    app.on_add_install_clicked( move ||
+    {
+        if let Some( handle ) = app_weak.upgrade()
+        {
+            controller::on_add_install_clicked( &handle, &gui_data_clone );
+        }
+    });
+
+    let app_weak = app.as_weak();
+    let gui_data_clone = plugin_data.clone();
+    app.window().on_close_requested( move ||
+    {
+        match app_weak.upgrade()
+        {
+            Some( handle ) => controller::on_close_requested( &handle, &gui_data_clone ),
+            None => CloseRequestResponse::HideWindow
+        }
+    });
+
+    // Script status is worked out off the UI thread, the list is redrawn when it comes in
+    let app_weak = app.as_weak();
+    plugin_data.borrow().health.start( move ||
+    {
+        let _ = app_weak.upgrade_in_event_loop( |handle| handle.invoke_list_view_changed() );
+    });
+
+    // Watch the plugin files for changes made by anything else while we're open
+    let watch_timer = std::rc::Rc::new( Timer::default() );
+    let timer_weak = std::rc::Rc::downgrade( &watch_timer );
+    let app_weak = app.as_weak();
+    let gui_data_clone = plugin_data.clone();
+    watch_timer.start( TimerMode::Repeated, Duration::from_secs( 2 ), move ||
+    {
+        let ( Some( handle ), Some( timer ) ) = ( app_weak.upgrade(), timer_weak.upgrade() ) else { return };
+        // Held while the user is asked about the change, or the next tick could ask again over the top of it
+        timer.stop();
+        controller::check_external_changes( &handle, &gui_data_clone );
+        timer.restart();
+    });
+
+    app.run()?;
+    plugin_data.borrow().health.stop();
+    controller::save_ui_prefs( &app, &plugin_data );
+
+    Ok(())
 }
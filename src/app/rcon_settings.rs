@@ -0,0 +1,158 @@
+/*
+	Sven Co-op Plugin Manager Version 2.0
+
+Copyright (C) 2025 Outerbeast
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program. If not, see <https://www.gnu.org/licenses/>.
+*/
+use std::thread;
+
+use slint::
+{
+    CloseRequestResponse,
+    ComponentHandle,
+    PlatformError
+};
+
+use crate::
+{
+    alloc_shared,
+    config::{ self, RconSettings },
+    rcon,
+    tr
+};
+
+use super::
+{
+    popup,
+    theme,
+    PopupButtons
+};
+
+pub type RconWindow = crate::RconWindow;
+
+fn settings_from_ui(window: &RconWindow) -> RconSettings
+{
+    let defaults = RconSettings::default();
+    let host = window.get_host().trim().to_string();
+
+    RconSettings
+    {
+        reload_on_save: window.get_reload_on_save(),
+        host: if host.is_empty() { defaults.host } else { host },
+        port: window.get_port().trim().parse().unwrap_or( defaults.port ),
+        password: window.get_password().to_string(),
+        extra: toml::Table::new()
+    }
+}
+// Shows the RCON settings of an install and saves them if OK is clicked. Returns whether they were saved.
+pub fn open_rcon_settings(install_name: &str) -> Result<bool, PlatformError>
+{
+    let window = RconWindow::new()?;
+    theme::apply( &window );
+
+    let rcon = config::read_store()
+        .unwrap_or_default()
+        .find_install( install_name )
+        .map( |i| i.rcon.clone() )
+    .unwrap_or_default();
+
+    let accepted = alloc_shared!( false );
+
+    window.set_install_name( install_name.into() );
+    window.set_host( rcon.host.into() );
+    window.set_port( rcon.port.to_string().into() );
+    window.set_password( rcon.password.into() );
+    window.set_reload_on_save( rcon.reload_on_save );
+    // Tried with what is in the fields, on another thread so the window isn't held up by a server that doesn't answer
+    let window_weak = window.as_weak();
+    window.on_test( move ||
+    {
+        let Some( w ) = window_weak.upgrade() else { return };
+        let settings = settings_from_ui( &w );
+
+        w.set_testing( true );
+        w.set_test_result( "".into() );
+
+        let window_weak = w.as_weak();
+        thread::spawn( move ||
+        {
+            let result = rcon::send_command( &settings, "status" );
+
+            let _ = window_weak.upgrade_in_event_loop( move |w|
+            {
+                w.set_testing( false );
+                w.set_test_failed( result.is_err() );
+                w.set_test_result(
+                match result
+                {
+                    Ok( reply ) => tr!( "Connected. {}", reply.lines().next().unwrap_or_default() ),
+                    Err( e ) => tr!( "Could not connect: {}", e )
+                }.into() );
+            });
+        });
+    });
+
+    let window_weak = window.as_weak();
+    let accepted_clone = accepted.clone();
+    window.on_accept( move ||
+    {
+        *accepted_clone.borrow_mut() = true;
+
+        if let Some( w ) = window_weak.upgrade()
+        {
+            let _ = w.hide();
+        }
+    });
+
+    let window_weak = window.as_weak();
+    window.on_cancel( move ||
+    {
+        if let Some( w ) = window_weak.upgrade()
+        {
+            let _ = w.hide();
+        }
+    });
+
+    window.window().on_close_requested( || CloseRequestResponse::HideWindow );
+    window.run()?;
+
+    if !accepted.take()
+    {
+        return Ok( false );
+    }
+    // Read again, the store may have been written while the window was open
+    let saved = config::read_store().and_then( |mut store|
+    {
+        let Some( install ) = store.installs.iter_mut().find( |i| i.name.eq_ignore_ascii_case( install_name ) ) else
+        {
+            return Ok( false );
+        };
+
+        let extra = std::mem::take( &mut install.rcon.extra );
+        install.rcon = RconSettings { extra, ..settings_from_ui( &window ) };
+
+        config::write_store( &store ).map( |_| true )
+    });
+
+    saved.or_else( |e|
+    {
+        popup( &tr!( "Error" ),
+            &tr!( "Failed to save the settings.\nReason: {}", e ),
+            "❌",
+            PopupButtons::Ok,
+            |_| { } );
+
+        Ok( false )
+    })
+}
@@ -0,0 +1,194 @@
+/*
+	Sven Co-op Plugin Manager Version 2.0
+
+Copyright (C) 2025 Outerbeast
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program. If not, see <https://www.gnu.org/licenses/>.
+*/
+use std::
+{
+    cell::RefCell,
+    fs,
+    path::{ Path, PathBuf },
+    rc::Rc
+};
+
+use slint::
+{
+    ComponentHandle,
+    ModelRc,
+    PlatformError,
+    SharedString,
+    VecModel
+};
+
+use crate::
+{
+    alloc_shared,
+    config::SVENCOOP_PATH,
+    highlight,
+    plugin::resolve_include,
+    tr,
+    SourceLine,
+    SourceToken
+};
+
+use super::theme;
+
+pub type ScriptViewerWindow = crate::ScriptViewer;
+
+#[derive( Default )]
+struct ViewerState
+{
+    current: Option<PathBuf>,
+    history: Vec<PathBuf>,// files left by following an #include, for going back
+    search_lines: Vec<String>// lowercase, to search without caring about case
+}
+fn load_file(viewer: &ScriptViewerWindow, state: &Rc<RefCell<ViewerState>>, path: &Path)
+{
+    let mut state = state.borrow_mut();
+    let source =
+    match fs::read( path )
+    {
+        Ok( bytes ) =>
+        {
+            viewer.set_error_text( SharedString::new() );
+            String::from_utf8_lossy( &bytes ).into_owned()
+        }
+
+        Err( e ) =>
+        {
+            viewer.set_error_text( tr!( "Could not open '{}'.\nReason: {}", path.display(), e ).into() );
+            String::new()
+        }
+    };
+
+    let lines: Vec<SourceLine> = highlight::highlight( &source )
+        .into_iter()
+        .zip( source.lines() )
+        .enumerate()
+        .map( |(i, (tokens, raw))| SourceLine
+        {
+            number: i as i32 + 1,
+            tokens: ModelRc::new( VecModel::from( tokens
+                .into_iter()
+                .map( |(kind, text)| SourceToken { text: text.into(), kind: kind as i32 } )
+            .collect::<Vec<_>>() ) ),
+            include: highlight::include_path( raw )
+                .and_then( |include| SVENCOOP_PATH.get().and_then( |dir| resolve_include( &dir, path, include ) ) )
+                .map( |p| p.display().to_string() )
+            .unwrap_or_default().into()
+        })
+    .collect();
+
+    state.search_lines = source.lines().map( str::to_lowercase ).collect();
+    state.current = Some( path.to_path_buf() );
+
+    viewer.set_file_name( path.file_name().map( |n| n.to_string_lossy().into_owned() ).unwrap_or_default().into() );
+    viewer.set_file_path( path.display().to_string().into() );
+    viewer.set_lines( ModelRc::new( VecModel::from( lines ) ) );
+    viewer.set_found_line( -1 );
+    viewer.set_search_status( SharedString::new() );
+    viewer.set_can_go_back( !state.history.is_empty() );
+    viewer.invoke_scroll_to_line( 0 );
+}
+// Finds the next line containing the text, going round to the other end when it runs out
+fn find_line(viewer: &ScriptViewerWindow, state: &ViewerState, text: &str, forward: bool)
+{
+    let text = text.to_lowercase();
+    let count = state.search_lines.len();
+
+    if text.is_empty() || count == 0
+    {
+        viewer.set_found_line( -1 );
+        viewer.set_search_status( SharedString::new() );
+        return;
+    }
+
+    let from = viewer.get_found_line();
+    let found = ( 1..=count )
+        .map( |step|
+        {
+            match forward
+            {
+                true => ( from + step as i32 ).rem_euclid( count as i32 ),
+                false => ( from - step as i32 ).rem_euclid( count as i32 )
+            }
+        })
+    .find( |i| state.search_lines[*i as usize].contains( &text ) );
+
+    match found
+    {
+        Some( line ) =>
+        {
+            viewer.set_found_line( line );
+            viewer.set_search_status( tr!( "Line {}", line + 1 ).into() );
+            viewer.invoke_scroll_to_line( line );
+        }
+
+        None =>
+        {
+            viewer.set_found_line( -1 );
+            viewer.set_search_status( tr!( "Not found" ).into() );
+        }
+    }
+}
+// Opens a window showing the script, it stays open alongside the main window
+pub fn open_script_viewer(path: &Path) -> Result<(), PlatformError>
+{
+    let viewer = ScriptViewerWindow::new()?;
+    theme::apply( &viewer );
+    let state = alloc_shared!( ViewerState::default() );
+
+    load_file( &viewer, &state, path );
+
+    let viewer_weak = viewer.as_weak();
+    let state_clone = state.clone();
+    viewer.on_search( move |text, forward|
+    {
+        if let Some( v ) = viewer_weak.upgrade()
+        {
+            find_line( &v, &state_clone.borrow(), &text, forward );
+        }
+    });
+
+    let viewer_weak = viewer.as_weak();
+    let state_clone = state.clone();
+    viewer.on_open_include( move |include|
+    {
+        let Some( v ) = viewer_weak.upgrade() else { return };
+        let current = state_clone.borrow().current.clone();
+
+        if let Some( current ) = current
+        {
+            state_clone.borrow_mut().history.push( current );
+        }
+
+        load_file( &v, &state_clone, Path::new( include.as_str() ) );
+    });
+
+    let viewer_weak = viewer.as_weak();
+    let state_clone = state.clone();
+    viewer.on_go_back( move ||
+    {
+        let Some( v ) = viewer_weak.upgrade() else { return };
+        let previous = state_clone.borrow_mut().history.pop();
+
+        if let Some( path ) = previous
+        {
+            load_file( &v, &state_clone, &path );
+        }
+    });
+
+    viewer.show()
+}
@@ -0,0 +1,164 @@
+/*
+	Sven Co-op Plugin Manager Version 2.0
+
+Copyright (C) 2025 Outerbeast
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program. If not, see <https://www.gnu.org/licenses/>.
+*/
+use slint::
+{
+    CloseRequestResponse,
+    ComponentHandle,
+    ModelRc,
+    PlatformError,
+    SharedString,
+    VecModel
+};
+
+use crate::
+{
+    alloc_shared,
+    config::{ self, ThemeMode },
+    i18n,
+    tr
+};
+
+use super::
+{
+    popup,
+    theme,
+    PopupButtons
+};
+
+pub type SettingsWindow = crate::SettingsWindow;
+
+// Shows the settings window and saves the settings if OK is clicked. Returns whether they were saved.
+pub fn open_settings() -> Result<bool, PlatformError>
+{
+    let window = SettingsWindow::new()?;
+    theme::apply( &window );
+    let store = config::read_store().unwrap_or_default();
+    let accepted = alloc_shared!( false );
+
+    window.set_editor_command( store.settings.editor_command.into() );
+    // Following the system comes first, then the bundled languages in the order they are listed
+    let language_names: Vec<SharedString> = std::iter::once( tr!( "System default" ).into() )
+        .chain( i18n::LANGUAGES.iter().map( |l| l.name.into() ) )
+    .collect();
+
+    let language_index = i18n::LANGUAGES.iter().position( |l| l.code == store.settings.language ).map_or( 0, |i| i + 1 );
+    window.set_language_names( ModelRc::new( VecModel::from( language_names ) ) );
+    window.set_language_index( language_index as i32 );
+    window.set_theme_index( store.settings.theme as i32 );
+    window.set_catalogue( store.settings.catalogue.into() );
+
+    let window_weak = window.as_weak();
+    window.on_browse_editor( move ||
+    {
+        let Some( w ) = window_weak.upgrade() else { return };
+
+        if let Some( path ) = rfd::FileDialog::new()
+            .set_title( tr!( "Select the editor to open scripts with" ) )
+        .pick_file()
+        {   // Quoted so paths with spaces survive being split back into a command
+            w.set_editor_command( format!( "\"{}\"", path.display() ).into() );
+        }
+    });
+
+    let window_weak = window.as_weak();
+    window.on_browse_catalogue( move ||
+    {
+        let Some( w ) = window_weak.upgrade() else { return };
+
+        if let Some( path ) = rfd::FileDialog::new()
+            .set_title( tr!( "Select a catalogue index" ) )
+            .add_filter( tr!( "Catalogue index" ), &["json"] )
+        .pick_file()
+        {
+            w.set_catalogue( path.display().to_string().into() );
+        }
+    });
+
+    let window_weak = window.as_weak();
+    let accepted_clone = accepted.clone();
+    window.on_accept( move ||
+    {
+        *accepted_clone.borrow_mut() = true;
+
+        if let Some( w ) = window_weak.upgrade()
+        {
+            let _ = w.hide();
+        }
+    });
+
+    let window_weak = window.as_weak();
+    window.on_cancel( move ||
+    {
+        if let Some( w ) = window_weak.upgrade()
+        {
+            let _ = w.hide();
+        }
+    });
+
+    window.window().on_close_requested( || CloseRequestResponse::HideWindow );
+    window.run()?;
+
+    if !accepted.take()
+    {
+        return Ok( false );
+    }
+    // Read again, the store may have been written while the window was open
+    let saved = config::read_store().and_then( |mut store|
+    {
+        store.settings.editor_command = window.get_editor_command().trim().to_string();
+        store.settings.language =
+        match window.get_language_index()
+        {
+            i if i > 0 => i18n::LANGUAGES.get( i as usize - 1 ).map( |l| l.code.to_string() ).unwrap_or_default(),
+            _ => String::new()
+        };
+        store.settings.theme =
+        match window.get_theme_index()
+        {
+            1 => ThemeMode::Light,
+            2 => ThemeMode::Dark,
+            3 => ThemeMode::HighContrast,
+            _ => ThemeMode::System
+        };
+        store.settings.catalogue = window.get_catalogue().trim().to_string();
+
+        config::write_store( &store ).map( |_| store.settings )
+    });
+
+    let settings =
+    match saved
+    {
+        Ok( settings ) => settings,
+        Err( e ) =>
+        {
+            popup( &tr!( "Error" ),
+                &tr!( "Failed to save the settings.\nReason: {}", e ),
+                "❌",
+                PopupButtons::Ok,
+                |_| { } );
+
+            return Ok( false );
+        }
+    };
+
+    i18n::set_language( &settings.language );
+    i18n::apply_to_ui();
+    theme::set_theme( settings.theme );
+
+    Ok( true )
+}
@@ -0,0 +1,46 @@
+/*
+	Sven Co-op Plugin Manager Version 2.0
+
+Copyright (C) 2025 Outerbeast
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program. If not, see <https://www.gnu.org/licenses/>.
+*/
+// The theme from the settings. Every window has its own copy of the Theme global in the UI, so it is set on each one as it is created.
+use std::sync::RwLock;
+
+use slint::{ ComponentHandle, Global };
+
+use crate::config::ThemeMode;
+
+static ACTIVE: RwLock<ThemeMode> = RwLock::new( ThemeMode::System );
+
+pub fn set_theme(mode: ThemeMode)
+{
+    if let Ok( mut active ) = ACTIVE.write()
+    {
+        *active = mode;
+    }
+}
+
+pub fn current_theme() -> ThemeMode
+{
+    ACTIVE.read().map( |a| *a ).unwrap_or_default()
+}
+// Draws a window in the theme in use, windows already open keep theirs until this is called on them again
+pub fn apply<'a, C>(window: &'a C)
+where
+    C: ComponentHandle,
+    crate::Theme<'a>: Global<'a, C>
+{
+    window.global::<crate::Theme>().set_mode( current_theme() as i32 );
+}
@@ -0,0 +1,242 @@
+/*
+	Sven Co-op Plugin Manager Version 2.0
+
+Copyright (C) 2025 Outerbeast
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program. If not, see <https://www.gnu.org/licenses/>.
+*/
+use std::
+{
+    path::PathBuf,
+    sync::{ Arc, Mutex, atomic::{ AtomicBool, Ordering } },
+    thread,
+    time::{ Duration, Instant }
+};
+
+use slint::
+{
+    CloseRequestResponse,
+    ComponentHandle,
+    Model,
+    ModelRc,
+    PlatformError,
+    StandardListViewItem,
+    VecModel,
+    Weak
+};
+
+use crate::
+{
+    alloc_locked,
+    alloc_shared,
+    config,
+    i18n,
+    tr
+};
+
+use super::
+{
+    popup,
+    theme,
+    PopupButtons
+};
+
+pub type SetupWindow = crate::SetupWizard;
+
+fn show_candidates(wizard: &SetupWindow, candidates: &[PathBuf])
+{
+    let items: Vec<StandardListViewItem> = candidates
+        .iter()
+        .map( |p| StandardListViewItem::from( format!( "{} - {}", config::Install::default_name( p ), p.display() ).as_str() ) )
+    .collect();
+
+    wizard.set_candidates( ModelRc::new( VecModel::from( items ) ) );
+}
+// Runs the install search on its own thread and feeds what it sees back to the window
+fn start_search(wizard: Weak<SetupWindow>, candidates: Arc<Mutex<Vec<PathBuf>>>, cancel: Arc<AtomicBool>)
+{
+    thread::spawn( move ||
+    {
+        let mut last_update = Instant::now();
+
+        config::discover_installs( &cancel,
+        |path|
+        {   // Don't flood the event loop, a few updates a second is plenty to read
+            if last_update.elapsed() < Duration::from_millis( 100 )
+            {
+                return;
+            }
+
+            last_update = Instant::now();
+            let path = path.display().to_string();
+            let _ = wizard.upgrade_in_event_loop( move |w| w.set_scanning( path.into() ) );
+        },
+        |dir|
+        {
+            if let Ok( mut list ) = candidates.lock() && !list.iter().any( |p| p == dir )
+            {
+                list.push( dir.to_path_buf() );
+                let list = list.clone();
+
+                let _ = wizard.upgrade_in_event_loop( move |w|
+                {
+                    show_candidates( &w, &list );
+
+                    if w.get_selected_index() < 0
+                    {
+                        w.set_selected_index( 0 );
+                    }
+                });
+            }
+        });
+
+        let cancelled = cancel.swap( false, Ordering::Relaxed );
+        let _ = wizard.upgrade_in_event_loop( move |w|
+        {
+            let count = w.get_candidates().row_count();
+            w.set_searching( false );
+            w.set_scanning( "".into() );
+            w.set_status(
+            match ( cancelled, count )
+            {
+                ( true, _ ) => tr!( "Search cancelled." ).into(),
+                ( false, 0 ) => tr!( "No installs were found. Use Browse... to pick the 'svencoop' folder yourself." ).into(),
+                ( false, n ) => tr!( "Search finished, installs found: {}", n ).into()
+            });
+        });
+    });
+}
+// Window for finding the game installs while the user watches and picking one. Used on first run, and when the stored install
+// has gone missing, in which case `known` has the other saved installs to choose from and a search is only run if asked for.
+// Returns the folder chosen followed by every other install listed, or nothing if the user quit.
+pub fn run_setup_wizard(notice: &str, known: Vec<PathBuf>) -> Result<Vec<PathBuf>, PlatformError>
+{
+    let wizard = SetupWindow::new()?;
+    i18n::apply_to_ui();
+    theme::apply( &wizard );
+
+    let search_now = known.is_empty();
+    let candidates = alloc_locked!( known );
+    let cancel = Arc::new( AtomicBool::new( false ) );
+    let chosen = alloc_shared!( None::<usize> );
+
+    wizard.set_notice( notice.into() );
+
+    match search_now
+    {
+        true => start_search( wizard.as_weak(), candidates.clone(), cancel.clone() ),
+        false =>
+        {
+            show_candidates( &wizard, &candidates.lock().map( |l| l.clone() ).unwrap_or_default() );
+            wizard.set_searching( false );
+            wizard.set_status( tr!( "Pick one of your other saved installs, or search again." ).into() );
+        }
+    }
+
+    let wizard_weak = wizard.as_weak();
+    let candidates_clone = candidates.clone();
+    let cancel_clone = cancel.clone();
+    wizard.on_search( move ||
+    {
+        if let Some( w ) = wizard_weak.upgrade()
+        {
+            w.set_searching( true );
+            w.set_status( tr!( "Searching for Sven Co-op installs..." ).into() );
+            start_search( w.as_weak(), candidates_clone.clone(), cancel_clone.clone() );
+        }
+    });
+
+    let cancel_clone = cancel.clone();
+    wizard.on_cancel_search( move || cancel_clone.store( true, Ordering::Relaxed ) );
+
+    let wizard_weak = wizard.as_weak();
+    let candidates_clone = candidates.clone();
+    wizard.on_browse( move ||
+    {
+        let Some( w ) = wizard_weak.upgrade() else { return };
+        let Some( folder ) = rfd::FileDialog::new()
+            .set_title( tr!( "Select the Sven Co-op \"svencoop\" folder" ) )
+        .pick_folder() else { return };
+        let Some( dir ) = config::resolve_svencoop_dir( &folder ) else
+        {
+            popup( &tr!( "Invalid Install" ),
+                &tr!( "'{}' does not look like a Sven Co-op install.\n\
+                Select the 'svencoop' folder containing '{}'.",
+                folder.display(), crate::plugin::FILENAME_PLUGINS ),
+                "❌",
+                PopupButtons::Ok,
+                |_| { } );
+
+            return;
+        };
+
+        let Ok( mut list ) = candidates_clone.lock() else { return };
+        let index =
+        match list.iter().position( |p| *p == dir )
+        {
+            Some( i ) => i,
+            None =>
+            {
+                list.push( dir );
+                list.len() - 1
+            }
+        };
+
+        show_candidates( &w, &list );
+        w.set_selected_index( index as i32 );
+    });
+
+    let wizard_weak = wizard.as_weak();
+    let chosen_clone = chosen.clone();
+    wizard.on_accept( move |i|
+    {
+        *chosen_clone.borrow_mut() = Some( i as usize );
+
+        if let Some( w ) = wizard_weak.upgrade()
+        {
+            let _ = w.hide();
+        }
+    });
+
+    let wizard_weak = wizard.as_weak();
+    wizard.on_quit( move ||
+    {
+        if let Some( w ) = wizard_weak.upgrade()
+        {
+            let _ = w.hide();
+        }
+    });
+
+    wizard.window().on_close_requested( || CloseRequestResponse::HideWindow );
+    wizard.run()?;
+    // The search thread may still be walking, let it go
+    if wizard.get_searching()
+    {
+        cancel.store( true, Ordering::Relaxed );
+    }
+
+    let mut found = candidates.lock().map( |l| l.clone() ).unwrap_or_default();
+
+    match chosen.take()
+    {
+        Some( i ) if i < found.len() =>
+        {
+            let dir = found.remove( i );
+            found.insert( 0, dir );
+
+            Ok( found )
+        }
+
+        _ => Ok( Vec::new() )
+    }
+}
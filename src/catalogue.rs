@@ -0,0 +1,432 @@
+/*
+	Sven Co-op Plugin Manager Version 2.0
+
+Copyright (C) 2025 Outerbeast
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program. If not, see <https://www.gnu.org/licenses/>.
+*/
+// The plugin catalogue: a JSON index of plugins that can be downloaded and installed in one go.
+// The index and the archives it points to can be local files or URLs, archives are either a single
+// script or a .zip laid out like the game's content folders.
+use std::
+{
+    env,
+    fs,
+    io::{ self, Cursor, Read },
+    path::{ Component, Path, PathBuf }
+};
+
+use sha2::{ Digest, Sha256 };
+
+use crate::
+{
+    plugin::{ PLUGINS_DIR, SCRIPT_EXT, addon_dir, check_plugin_name, check_script_path },
+    tr,
+    utils,
+    APPNAME
+};
+
+// Nothing in a plugin catalogue comes near this, it stops a bad link from filling the memory
+const MAX_DOWNLOAD: u64 = 64 * 1024 * 1024;
+// Same for what a .zip unpacks to, a small archive can hold far more than it looks
+const MAX_UNPACKED: u64 = 256 * 1024 * 1024;
+// Top folders of a .zip that go straight into svencoop_addon, anything else is taken as being inside scripts/plugins
+const CONTENT_DIRS: &[&str] = &["scripts", "sound", "models", "sprites", "maps", "gfx", "resource", "events"];
+
+// One plugin listed in the catalogue
+#[derive( Debug, Clone, Default, PartialEq, serde::Deserialize )]
+#[serde( default )]
+pub struct CatalogueEntry
+{
+    pub name: String,
+    pub description: String,
+    pub author: String,
+    pub version: String,
+    pub archive: String,// file or URL, relative to the index if it isn't either
+    pub sha256: String,// of the archive, in hex
+    pub script: String// the script to register, from scripts/plugins without .as. Worked out from the archive when empty.
+}
+
+#[derive( Debug, Default, serde::Deserialize )]
+#[serde( default )]
+pub struct Catalogue
+{
+    pub plugins: Vec<CatalogueEntry>
+}
+
+impl CatalogueEntry
+{   // Every word has to be found in the name, description or author
+    pub fn matches(&self, filter: &str) -> bool
+    {
+        filter.split_whitespace().all( |word|
+        {
+            [&self.name, &self.description, &self.author].iter().any( |field| utils::fuzzy_match( word, field ) )
+        })
+    }
+}
+
+fn is_url(location: &str) -> bool
+{
+    let lower = location.trim().to_ascii_lowercase();
+    lower.starts_with( "http://" ) || lower.starts_with( "https://" )
+}
+// Reads a file or downloads a URL
+pub fn fetch(location: &str) -> io::Result<Vec<u8>>
+{
+    let location = location.trim();
+
+    if !is_url( location )
+    {
+        return fs::read( location.strip_prefix( "file://" ).unwrap_or( location ) );
+    }
+
+    let mut response = ureq::get( location ).call().map_err( io::Error::other )?;
+
+    response.body_mut()
+        .with_config()
+        .limit( MAX_DOWNLOAD )
+        .read_to_vec()
+    .map_err( io::Error::other )
+}
+// Where an archive is, worked out from the index when it is given relative to it
+pub fn resolve_location(index: &str, archive: &str) -> String
+{
+    let ( index, archive ) = ( index.trim(), archive.trim() );
+
+    if is_url( archive ) || Path::new( archive ).is_absolute()
+    {
+        return archive.to_string();
+    }
+
+    match is_url( index )
+    {
+        true => match index.rfind( '/' )
+        {
+            Some( i ) => format!( "{}/{}", &index[..i], archive.trim_start_matches( "./" ) ),
+            None => archive.to_string()
+        },
+
+        false =>
+        {
+            let index = Path::new( index.strip_prefix( "file://" ).unwrap_or( index ) );
+            index.parent().unwrap_or( Path::new( "" ) ).join( archive ).to_string_lossy().into_owned()
+        }
+    }
+}
+
+pub fn load_catalogue(location: &str) -> io::Result<Catalogue>
+{
+    let bytes = fetch( location )?;
+
+    let mut catalogue: Catalogue = serde_json::from_slice( &bytes )
+        .map_err( |e| io::Error::new( io::ErrorKind::InvalidData, tr!( "The catalogue could not be read: {}", e ) ) )?;
+
+    catalogue.plugins.retain( |p| !p.name.trim().is_empty() );
+    catalogue.plugins.sort_unstable_by_key( |p| p.name.to_lowercase() );
+
+    Ok( catalogue )
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String
+{
+    Sha256::digest( bytes ).iter().map( |b| format!( "{:02x}", b ) ).collect()
+}
+// Where a file in a .zip goes inside svencoop_addon, None for anything that would end up outside of it
+fn addon_path(entry: &Path) -> Option<PathBuf>
+{
+    if entry.components().any( |c| !matches!( c, Component::Normal( _ ) ) )
+    {
+        return None;
+    }
+
+    let top = entry.components().next()?.as_os_str().to_string_lossy().to_ascii_lowercase();
+
+    match CONTENT_DIRS.contains( &top.as_str() )
+    {
+        true => Some( entry.to_path_buf() ),
+        false => Some( Path::new( PLUGINS_DIR ).join( entry ) )
+    }
+}
+// Where a download is fetched, checked and unpacked before anything is copied into the game
+fn staging_dir(sha256: &str) -> PathBuf
+{
+    env::temp_dir().join( format!( "{}-catalogue", APPNAME ) ).join( format!( "{}-{}", std::process::id(), sha256.get( ..16 ).unwrap_or( sha256 ) ) )
+}
+// Unpacks every file of a .zip into `staging`, laid out as they go in svencoop_addon. Returns their paths from there.
+// The paths are all checked first, so an archive reaching outside the game folder unpacks nothing.
+// Unpacking stops with an error once more than `limit` bytes have come out.
+fn extract_zip(bytes: &[u8], staging: &Path, limit: u64) -> io::Result<Vec<PathBuf>>
+{
+    let invalid = |e: zip::result::ZipError| io::Error::new( io::ErrorKind::InvalidData, e );
+    let mut archive = zip::ZipArchive::new( Cursor::new( bytes ) ).map_err( invalid )?;
+    let mut files = Vec::new();
+
+    for i in 0..archive.len()
+    {
+        let file = archive.by_index( i ).map_err( invalid )?;
+
+        if file.is_dir()
+        {
+            continue;
+        }
+
+        match file.enclosed_name().as_deref().and_then( addon_path )
+        {
+            Some( relative ) => files.push( ( i, relative ) ),
+            None => return Err( io::Error::new( io::ErrorKind::InvalidData, tr!( "The archive has a file outside of the game folder: {}", file.name() ) ) )
+        }
+    }
+
+    let mut left = limit;
+
+    for ( i, relative ) in &files
+    {
+        let file = archive.by_index( *i ).map_err( invalid )?;
+        let dst = staging.join( relative );
+
+        if let Some( parent ) = dst.parent()
+        {
+            fs::create_dir_all( parent )?;
+        }
+        // The sizes in the archive can't be trusted, so what is read out is counted instead. One byte over the limit is enough to tell.
+        let written = io::copy( &mut file.take( left + 1 ), &mut fs::File::create( &dst )? )?;
+
+        if written > left
+        {
+            return Err( io::Error::new( io::ErrorKind::InvalidData, tr!( "The archive unpacks to more than {} MB", limit / 1024 / 1024 ) ) );
+        }
+
+        left -= written;
+    }
+
+    Ok( files.into_iter().map( |(_, relative)| relative ).collect() )
+}
+// The script a .zip's plugin is loaded from, when the catalogue doesn't say: the only script, or the only one with a PluginInit()
+fn find_plugin_script(staging: &Path, files: &[PathBuf]) -> Option<String>
+{
+    let scripts: Vec<&Path> = files
+        .iter()
+        .filter_map( |f| f.strip_prefix( PLUGINS_DIR ).ok() )
+        .filter( |f| f.extension().is_some_and( |e| e.eq_ignore_ascii_case( SCRIPT_EXT ) ) )
+    .collect();
+
+    let name = |script: &Path| script.with_extension( "" ).to_string_lossy().replace( '\\', "/" );
+
+    if let [script] = scripts.as_slice()
+    {
+        return Some( name( script ) );
+    }
+
+    let mut with_init = scripts.iter().filter( |s|
+    {
+        fs::read( staging.join( PLUGINS_DIR ).join( s ) )
+            .is_ok_and( |bytes| String::from_utf8_lossy( &bytes ).contains( "PluginInit" ) )
+    });
+
+    match ( with_init.next(), with_init.next() )
+    {
+        ( Some( script ), None ) => Some( name( script ) ),
+        _ => None
+    }
+}
+// The file a location points at, without any query or fragment on the end of a URL
+fn file_name(location: &str) -> &str
+{
+    let location = location.split( ['?', '#'] ).next().unwrap_or( location );
+    location.rsplit( ['/', '\\'] ).next().unwrap_or_default()
+}
+// A catalogue entry whose name or script would break the plugin file, or point outside of scripts/plugins
+fn check_entry(name: &str, script: &str) -> io::Result<()>
+{
+    let problem = check_plugin_name( name ).or_else( || match script.trim().is_empty() { true => None, false => check_script_path( script ) } );
+
+    match problem
+    {
+        Some( problem ) => Err( io::Error::new( io::ErrorKind::InvalidData, tr!( "The catalogue entry for '{}' can't be used: {}", name, problem ) ) ),
+        None => Ok( () )
+    }
+}
+// A plugin that has been downloaded, checked against its checksum and unpacked, ready to go in svencoop_addon.
+// Its staging folder is removed when it is dropped, installed or not.
+pub struct Download
+{
+    staging: PathBuf,
+    files: Vec<PathBuf>,// from svencoop_addon
+    script: String// to register the plugin with
+}
+
+impl Drop for Download
+{
+    fn drop(&mut self)
+    {
+        let _ = fs::remove_dir_all( &self.staging );
+    }
+}
+// Downloads a plugin and unpacks it to a staging folder. Nothing goes near the game unless the download matches the catalogue's checksum.
+pub fn download(entry: &CatalogueEntry, index: &str) -> io::Result<Download>
+{
+    check_entry( &entry.name, &entry.script )?;
+    let sha256 = entry.sha256.trim().to_ascii_lowercase();
+
+    if sha256.is_empty()
+    {
+        return Err( io::Error::new( io::ErrorKind::InvalidData, tr!( "The catalogue has no checksum for '{}'", entry.name ) ) );
+    }
+
+    let location = resolve_location( index, &entry.archive );
+    let bytes = fetch( &location )?;
+
+    if sha256_hex( &bytes ) != sha256
+    {
+        return Err( io::Error::new( io::ErrorKind::InvalidData, tr!( "The download of '{}' doesn't match the catalogue's checksum", entry.name ) ) );
+    }
+
+    let staging = staging_dir( &sha256 );
+    let _ = fs::remove_dir_all( &staging );
+    fs::create_dir_all( &staging )?;
+    // From here on dropping it on an error cleans the staging folder up
+    let mut download = Download { staging, files: Vec::new(), script: entry.script.trim().to_string() };
+    let file_name = file_name( &location );
+
+    if file_name.to_ascii_lowercase().ends_with( &format!( ".{}", SCRIPT_EXT ) )
+    {   // A lone script goes in scripts/plugins, same as one added from a file
+        let relative = Path::new( PLUGINS_DIR ).join( file_name );
+        fs::create_dir_all( download.staging.join( PLUGINS_DIR ) )?;
+        fs::write( download.staging.join( &relative ), &bytes )?;
+
+        download.files.push( relative );
+
+        if download.script.is_empty()
+        {
+            download.script = Path::new( file_name ).with_extension( "" ).to_string_lossy().into_owned();
+        }
+    }
+    else
+    {
+        download.files = extract_zip( &bytes, &download.staging, MAX_UNPACKED )?;
+
+        if download.script.is_empty()
+        {
+            download.script = find_plugin_script( &download.staging, &download.files ).ok_or_else( ||
+            {
+                io::Error::new( io::ErrorKind::InvalidData, tr!( "Could not tell which script of '{}' is the plugin, the catalogue should name it", entry.name ) )
+            })?;
+        }
+    }
+    // Once more for a script worked out from the download
+    check_entry( &entry.name, &download.script )?;
+
+    Ok( download )
+}
+
+impl Download
+{   // Files already in svencoop_addon that installing would replace, from svencoop_addon
+    pub fn collisions(&self, svencoop_dir: &Path) -> Vec<&Path>
+    {
+        let addon_dir = addon_dir( svencoop_dir );
+        self.files.iter().filter( |f| addon_dir.join( f ).exists() ).map( PathBuf::as_path ).collect()
+    }
+    // The files to copy into svencoop_addon, as (unpacked to, path from svencoop_addon)
+    pub fn files(&self) -> impl Iterator<Item = (PathBuf, &Path)>
+    {
+        self.files.iter().map( |f| ( self.staging.join( f ), f.as_path() ) )
+    }
+    // The script to register the plugin with
+    pub fn script(&self) -> &str
+    {
+        &self.script
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use std::io::Write;
+
+    fn scratch_dir(name: &str) -> PathBuf
+    {
+        let dir = env::temp_dir().join( format!( "scpm-catalogue-{}-{}", name, std::process::id() ) );
+        let _ = fs::remove_dir_all( &dir );
+        fs::create_dir_all( &dir ).unwrap();
+
+        dir
+    }
+
+    fn zip_of(files: &[(&str, &[u8])]) -> Vec<u8>
+    {
+        let mut writer = zip::ZipWriter::new( Cursor::new( Vec::new() ) );
+
+        for ( name, contents ) in files
+        {
+            writer.start_file( *name, zip::write::SimpleFileOptions::default() ).unwrap();
+            writer.write_all( contents ).unwrap();
+        }
+
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn extract_zip_lays_files_out_as_in_svencoop_addon()
+    {
+        let staging = scratch_dir( "layout" );
+        let bytes = zip_of( &[("MyPlugin.as", b"void PluginInit() {}"), ("sound/my/beep.wav", b"RIFF")] );
+
+        let files = extract_zip( &bytes, &staging, MAX_UNPACKED ).unwrap();
+
+        assert_eq!( files, [Path::new( PLUGINS_DIR ).join( "MyPlugin.as" ), PathBuf::from( "sound/my/beep.wav" )] );
+        assert!( files.iter().all( |f| staging.join( f ).is_file() ) );
+
+        let _ = fs::remove_dir_all( &staging );
+    }
+
+    #[test]
+    fn extract_zip_unpacks_nothing_when_a_path_leaves_the_game_folder()
+    {
+        for name in ["../escape.as", "scripts/../../escape.as", "/etc/escape.as"]
+        {
+            let staging = scratch_dir( "traversal" );
+            let bytes = zip_of( &[("MyPlugin.as", b"void PluginInit() {}"), (name, b"nope")] );
+
+            let result = extract_zip( &bytes, &staging, MAX_UNPACKED );
+
+            assert_eq!( result.map_err( |e| e.kind() ), Err( io::ErrorKind::InvalidData ), "{}", name );
+            assert_eq!( fs::read_dir( &staging ).unwrap().count(), 0, "{}", name );
+            assert!( !staging.parent().unwrap().join( "escape.as" ).exists() );
+
+            let _ = fs::remove_dir_all( &staging );
+        }
+    }
+
+    #[test]
+    fn extract_zip_stops_at_the_size_limit()
+    {
+        let staging = scratch_dir( "limit" );
+        let bytes = zip_of( &[("a.as", &[b' '; 600]), ("b.as", &[b' '; 600])] );
+
+        assert!( extract_zip( &bytes, &staging, 1200 ).is_ok() );
+        assert_eq!( extract_zip( &bytes, &staging, 1000 ).map_err( |e| e.kind() ), Err( io::ErrorKind::InvalidData ) );
+
+        let _ = fs::remove_dir_all( &staging );
+    }
+
+    #[test]
+    fn check_entry_rejects_names_and_scripts_that_would_escape()
+    {
+        assert!( check_entry( "My Plugin", "MyPlugin" ).is_ok() );
+        assert!( check_entry( "My Plugin", "" ).is_ok() );
+        assert!( check_entry( "My \"Plugin\"", "MyPlugin" ).is_err() );
+        assert!( check_entry( "My Plugin", "../../maps/MyPlugin" ).is_err() );
+        assert!( check_entry( "My Plugin", "My\"Plugin" ).is_err() );
+    }
+}
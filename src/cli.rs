@@ -0,0 +1,140 @@
+/*
+	Sven Co-op Plugin Manager Version 2.0
+
+Copyright (C) 2025 Outerbeast
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program. If not, see <https://www.gnu.org/licenses/>.
+*/
+use std::
+{
+    collections::HashMap,
+    path::{ Path, PathBuf }
+};
+
+use crate::
+{
+    compile_log,
+    health::ScriptHealth,
+    plugin::{ PluginEntry, PluginState },
+    tr
+};
+// Command line flags. Anything that isn't a flag is a plugin script to install, so dragging files onto the exe still works.
+#[derive( Debug, Default )]
+pub struct Args
+{
+    pub install: Option<String>,// --install <name>
+    pub game_dir: Option<PathBuf>,// --game-dir <path>, overrides the stored installs for this run
+    pub remember: bool,// --remember, stores the --game-dir install in the config
+    pub show: Option<Vec<String>>,// show [name...], prints the plugins instead of opening the window. Empty shows them all.
+    pub files: Vec<String>
+}
+
+pub fn parse<I>(args: I) -> Result<Args, String>
+where I: IntoIterator<Item = String>
+{
+    let mut parsed = Args::default();
+    let mut args = args.into_iter();
+
+    while let Some( arg ) = args.next()
+    {
+        match arg.as_str()
+        {
+            "--install" =>
+            {
+                parsed.install = Some( args.next().ok_or_else( || tr!( "{} requires an install name", "--install" ) )? );
+            }
+
+            "--game-dir" =>
+            {
+                parsed.game_dir = Some( PathBuf::from( args.next().ok_or_else( || tr!( "{} requires a path", "--game-dir" ) )? ) );
+            }
+
+            "--remember" => parsed.remember = true,
+            // Only as the first word, a script can't be called "show" as it has no .as on the end anyway
+            "show" if parsed.show.is_none() && parsed.files.is_empty() => parsed.show = Some( Vec::new() ),
+
+            _ =>
+            match parsed.show.as_mut()
+            {
+                Some( names ) => names.push( arg ),
+                None => parsed.files.push( arg )
+            }
+        }
+    }
+
+    Ok( parsed )
+}
+// The plugins asked for by "show", one block each with the script status and any compile errors in the server logs.
+// Gives back the text and the names that didn't match a plugin.
+pub fn show_plugins(svencoop_dir: &Path, plugins: &HashMap<String, PluginEntry>, names: &[String]) -> (String, Vec<String>)
+{
+    let mut shown: Vec<&PluginEntry> =
+    match names.is_empty()
+    {
+        true => plugins.values().collect(),
+        false => plugins.values().filter( |p| names.iter().any( |n| n.eq_ignore_ascii_case( &p.name ) ) ).collect()
+    };
+
+    shown.sort_unstable_by_key( |p| p.name.to_ascii_lowercase() );
+
+    let unknown = names
+        .iter()
+        .filter( |n| !shown.iter().any( |p| n.eq_ignore_ascii_case( &p.name ) ) )
+        .cloned()
+    .collect();
+
+    let logged = compile_log::read_compile_errors( svencoop_dir );
+    let mut out = String::new();
+
+    for plugin in shown
+    {
+        let state =
+        match plugin.state
+        {
+            PluginState::Enabled => tr!( "enabled" ),
+            _ => tr!( "disabled" )
+        };
+
+        out.push_str( &format!( "{} [{}]\n", plugin.name, state ) );
+        out.push_str( &format!( "    {}: {}\n", tr!( "Script" ), plugin.script ) );
+        out.push_str( &format!( "    {}: {}\n", tr!( "Status" ), ScriptHealth::check( svencoop_dir, &plugin.script ).describe() ) );
+        out.push_str( &format!( "    {}: {}\n", tr!( "Admin Level" ), plugin.adminlevel as isize ) );
+
+        for ( label, value ) in
+        [
+            ( tr!( "Command NS" ), &plugin.concommandns ),
+            ( tr!( "Included Maps" ), &plugin.maps_included ),
+            ( tr!( "Excluded Maps" ), &plugin.maps_excluded )
+        ]
+        {
+            if !value.trim().is_empty()
+            {
+                out.push_str( &format!( "    {}: {}\n", label, value ) );
+            }
+        }
+
+        let errors = compile_log::errors_for_script( svencoop_dir, &plugin.script, &logged );
+
+        if !errors.is_empty()
+        {
+            out.push_str( &format!( "    {}\n", tr!( "The server log has compile errors for this script:" ) ) );
+
+            for error in errors
+            {
+                out.push_str( &format!( "        {}\n", error.describe() ) );
+            }
+        }
+    }
+
+    ( out, unknown )
+}
@@ -0,0 +1,273 @@
+/*
+	Sven Co-op Plugin Manager Version 2.0
+
+Copyright (C) 2025 Outerbeast
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program. If not, see <https://www.gnu.org/licenses/>.
+*/
+use std::
+{
+    fs,
+    collections::HashSet,
+    hash::{ DefaultHasher, Hash, Hasher },
+    path::{ Path, PathBuf },
+    time::SystemTime
+};
+
+use chrono::{ Local, NaiveDateTime, TimeZone };
+
+use crate::
+{
+    highlight,
+    plugin::{ SCRIPT_EXT, resolve_include, resolve_script_path }
+};
+
+pub const LOGS_DIR: &str = "logs";
+pub const LOG_EXT: &str = "log";
+// Only the newest logs are read, older ones are likely about scripts that have been fixed since
+const LOGS_TO_READ: usize = 3;
+// Includes can include each other, this stops a loop of them going on forever
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+// One error the AngelScript compiler wrote to a server log
+#[derive( Debug, Clone, PartialEq )]
+pub struct CompileError
+{
+    pub file: String,// as written in the log, e.g. "scripts/plugins/MyPlugin.as"
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    pub logged: Option<SystemTime>// from the line's timestamp, or when its log was last written if it has none
+}
+
+impl CompileError
+{
+    pub fn describe(&self) -> String
+    {
+        format!( "{} ({}, {}): {}", self.file, self.line, self.column, self.message )
+    }
+}
+// When a log line was written, from its "L 10/19/2025 - 20:11:47:" prefix. The server logs in its local time.
+fn line_time(line: &str) -> Option<SystemTime>
+{
+    let stamp = line.strip_prefix( "L " )?.get( ..21 )?;
+    let time = NaiveDateTime::parse_from_str( stamp, "%m/%d/%Y - %H:%M:%S" ).ok()?;
+
+    Local.from_local_datetime( &time ).earliest().map( SystemTime::from )
+}
+// Reads a compiler error out of a log line such as
+// "L 10/19/2025 - 20:11:47: scripts/plugins/MyPlugin.as (25, 3) : ERR  : Expected ';'"
+pub fn parse_log_line(line: &str) -> Option<CompileError>
+{
+    let ( head, message ) = line.split_once( " : ERR" )?;
+    let message = message.trim_start_matches( [' ', ':'] ).trim();
+
+    let open = head.rfind( '(' )?;
+    let ( line_no, column ) = head[open + 1..].trim().strip_suffix( ')' )?.split_once( ',' )?;
+    let file = head[..open].trim_end();
+
+    if !file.to_ascii_lowercase().ends_with( &format!( ".{}", SCRIPT_EXT ) )
+    {
+        return None;
+    }
+    // Anything before the scripts folder is the log's own prefix: date, time, "Angelscript:" and so on
+    let file =
+    match file.to_ascii_lowercase().replace( '\\', "/" ).find( "scripts/" )
+    {
+        Some( i ) => &file[i..],
+        None => file.rsplit( ' ' ).next().unwrap_or( file )
+    };
+
+    Some( CompileError
+    {
+        file: file.replace( '\\', "/" ),
+        line: line_no.trim().parse().ok()?,
+        column: column.trim().parse().ok()?,
+        message: message.to_string(),
+        logged: line_time( line )
+    })
+}
+// The newest server logs, newest first
+fn recent_logs(svencoop_dir: &Path) -> Vec<(PathBuf, Option<SystemTime>)>
+{
+    let Ok( entries ) = fs::read_dir( svencoop_dir.join( LOGS_DIR ) ) else { return Vec::new() };
+
+    let mut logs: Vec<_> = entries
+        .filter_map( Result::ok )
+        .map( |e| e.path() )
+        .filter( |p| p.is_file() && p.extension().is_some_and( |e| e.eq_ignore_ascii_case( LOG_EXT ) ) )
+        .map( |p|
+        {
+            let modified = fs::metadata( &p ).and_then( |m| m.modified() ).ok();
+            ( p, modified )
+        })
+    .collect();
+
+    logs.sort_unstable_by( |a, b| b.1.cmp( &a.1 ).then_with( || b.0.cmp( &a.0 ) ) );
+    logs.truncate( LOGS_TO_READ );
+
+    logs
+}
+// Changes whenever a log is added or written to
+pub fn logs_signature(svencoop_dir: &Path) -> u64
+{
+    let mut hasher = DefaultHasher::new();
+    recent_logs( svencoop_dir ).hash( &mut hasher );
+
+    hasher.finish()
+}
+// Every compiler error in the newest logs, newest log first. The same error logged again is only kept once.
+pub fn read_compile_errors(svencoop_dir: &Path) -> Vec<CompileError>
+{
+    let mut seen = HashSet::new();
+    let mut errors = Vec::new();
+
+    for ( path, modified ) in recent_logs( svencoop_dir )
+    {
+        let Ok( bytes ) = fs::read( &path ) else { continue };
+
+        for line in String::from_utf8_lossy( &bytes ).lines()
+        {
+            let Some( mut error ) = parse_log_line( line ) else { continue };
+
+            if seen.insert( ( error.file.to_ascii_lowercase(), error.line, error.column, error.message.clone() ) )
+            {
+                error.logged = error.logged.or( modified );
+                errors.push( error );
+            }
+        }
+    }
+
+    errors
+}
+// How a file is named in the logs: its path from the svencoop or svencoop_addon folder, lower case with forward slashes
+fn log_name(svencoop_dir: &Path, path: &Path) -> Option<String>
+{
+    let root = svencoop_dir.parent().unwrap_or( svencoop_dir );
+    let relative = path.strip_prefix( root ).ok()?;
+    // Drop the content folder, whichever of them it is in
+    let relative: PathBuf = relative.components().skip( 1 ).collect();
+
+    Some( relative.to_string_lossy().replace( '\\', "/" ).to_ascii_lowercase() )
+}
+// A plugin's script and everything it includes, as (path on disk, name in the logs)
+pub fn script_files(svencoop_dir: &Path, script: &str) -> Vec<(PathBuf, String)>
+{
+    let mut files = Vec::new();
+    let mut seen = HashSet::new();
+    let mut pending: Vec<(PathBuf, usize)> = resolve_script_path( svencoop_dir, script ).into_iter().map( |p| ( p, 0 ) ).collect();
+
+    while let Some( ( path, depth ) ) = pending.pop()
+    {
+        if !seen.insert( path.clone() )
+        {
+            continue;
+        }
+
+        if let Some( name ) = log_name( svencoop_dir, &path )
+        {
+            files.push( ( path.clone(), name ) );
+        }
+
+        if depth >= MAX_INCLUDE_DEPTH
+        {
+            continue;
+        }
+
+        let Ok( bytes ) = fs::read( &path ) else { continue };
+
+        for line in String::from_utf8_lossy( &bytes ).lines()
+        {
+            if let Some( include ) = highlight::include_path( line )
+            && let Some( included ) = resolve_include( svencoop_dir, &path, include )
+            {
+                pending.push( ( included, depth + 1 ) );
+            }
+        }
+    }
+
+    files
+}
+// The errors that belong to a plugin's script or one of its includes, `files` as given by script_files.
+// An error logged before its file was last saved has probably been fixed, so it is left out.
+pub fn errors_in_files(files: &[(PathBuf, String)], errors: &[CompileError]) -> Vec<CompileError>
+{
+    errors
+        .iter()
+        .filter( |error|
+        {
+            let name = error.file.to_ascii_lowercase();
+
+            files.iter().any( |(path, file)|
+            {
+                let edited = fs::metadata( path ).and_then( |m| m.modified() ).ok();
+                *file == name && !matches!( ( edited, error.logged ), ( Some( e ), Some( l ) ) if e > l )
+            })
+        })
+        .cloned()
+    .collect()
+}
+// Same as errors_in_files, finding the script's files first. For a one-off look, the health monitor keeps its own.
+pub fn errors_for_script(svencoop_dir: &Path, script: &str, errors: &[CompileError]) -> Vec<CompileError>
+{
+    match errors.is_empty()
+    {
+        true => Vec::new(),
+        false => errors_in_files( &script_files( svencoop_dir, script ), errors )
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn parse_log_line_reads_an_error()
+    {
+        let line = "L 10/19/2025 - 20:11:47: Angelscript: scripts/plugins/MyPlugin.as (25, 3) : ERR  : Expected ';'";
+        let error = parse_log_line( line ).unwrap();
+        let stamp = Local.with_ymd_and_hms( 2025, 10, 19, 20, 11, 47 ).earliest().map( SystemTime::from );
+
+        assert_eq!( error.file, "scripts/plugins/MyPlugin.as" );
+        assert_eq!( ( error.line, error.column ), ( 25, 3 ) );
+        assert_eq!( error.message, "Expected ';'" );
+        assert_eq!( error.logged, stamp );
+    }
+
+    #[test]
+    fn parse_log_line_handles_backslashes_and_missing_timestamps()
+    {
+        let error = parse_log_line( r"scripts\plugins\Sub Folder\Thing.AS (7,12) : ERR : No matching signatures" ).unwrap();
+
+        assert_eq!( error.file, "scripts/plugins/Sub Folder/Thing.AS" );
+        assert_eq!( ( error.line, error.column ), ( 7, 12 ) );
+        assert_eq!( error.logged, None );
+    }
+
+    #[test]
+    fn parse_log_line_skips_lines_that_are_not_script_errors()
+    {
+        for line in
+        [
+            "L 10/19/2025 - 20:11:47: Angelscript: scripts/plugins/MyPlugin.as (25, 3) : WARN : Unused variable",
+            "L 10/19/2025 - 20:11:47: maps/stadium4.bsp (1, 1) : ERR : Not a script",
+            "L 10/19/2025 - 20:11:47: scripts/plugins/MyPlugin.as : ERR : No position",
+            "L 10/19/2025 - 20:11:47: scripts/plugins/MyPlugin.as (x, 3) : ERR : Bad line number",
+            "L 10/19/2025 - 20:11:47: \"Player<1><STEAM_0:0:1><>\" connected"
+        ]
+        {
+            assert_eq!( parse_log_line( line ), None, "{}", line );
+        }
+    }
+}
@@ -20,8 +20,12 @@ use std::
     env,
     fs,
     io,
-    path::PathBuf,
-    sync::OnceLock
+    path::{ Path, PathBuf },
+    sync::
+    {
+        RwLock,
+        atomic::{ AtomicBool, Ordering }
+    }
 };
 
 use crate::
@@ -31,15 +35,248 @@ use crate::
         FILENAME_DISABLED_PLUGINS,
         FILENAME_PLUGINS
     },
+    steam,
+    tr,
     utils
 };
 
-pub static SVENCOOP_PATH: OnceLock<PathBuf> = OnceLock::new();
+// Path to the "svencoop" folder of the install currently being managed, changes when switching installs
+pub struct GamePath( RwLock<Option<PathBuf>> );
+
+impl GamePath
+{
+    pub fn get(&self) -> Option<PathBuf>
+    {
+        self.0.read().ok().and_then( |p| p.clone() )
+    }
+
+    pub fn set(&self, path: PathBuf)
+    {
+        if let Ok( mut p ) = self.0.write()
+        {
+            *p = Some( path );
+        }
+    }
+}
+
+pub const ENV_GAME_DIR: &str = "SCPM_GAME_DIR";
+
+pub static SVENCOOP_PATH: GamePath = GamePath( RwLock::new( None ) );
+// Column the plugin list is sorted by
+#[derive( Debug, Default, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize )]
+#[serde( rename_all = "lowercase" )]
+pub enum SortColumn
+{
+    State,
+    #[default]
+    Name,
+    Script
+}
+// Main window size, position and list column layout, shared by every install
+#[derive( Debug, Default, Clone, serde::Serialize, serde::Deserialize )]
+#[serde( default )]
+pub struct WindowPrefs
+{
+    pub x: Option<i32>,
+    pub y: Option<i32>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub name_column_width: Option<f32>,
+    #[serde( flatten )]
+    pub extra: toml::Table// fields from other versions of the app, kept as they are
+}
+// Colours the windows are drawn with
+#[derive( Debug, Default, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize )]
+#[serde( rename_all = "kebab-case" )]
+pub enum ThemeMode
+{
+    #[default]
+    System,
+    Light,
+    Dark,
+    HighContrast
+}
+// App-wide options changed in the settings window
+#[derive( Debug, Default, Clone, serde::Serialize, serde::Deserialize )]
+#[serde( default )]
+pub struct Settings
+{
+    pub editor_command: String,// opens scripts, "{file}" is replaced by the script's path or it is added to the end
+    pub language: String,// code of a bundled translation, empty to follow the system
+    pub theme: ThemeMode,
+    pub catalogue: String,// where the plugin catalogue's index is read from, a file or a URL
+    #[serde( flatten )]
+    pub extra: toml::Table// fields from other versions of the app, kept as they are
+}
+// How the plugin list was left for an install
+#[derive( Debug, Default, Clone, serde::Serialize, serde::Deserialize )]
+#[serde( default )]
+pub struct ViewPrefs
+{
+    pub last_plugin: Option<String>,
+    pub sort_column: SortColumn,
+    pub sort_descending: bool,
+    pub filter: String,
+    pub show_enabled: bool,// quick filters
+    pub show_disabled: bool,
+    pub show_missing_script: bool,
+    pub show_map_restricted: bool,
+    #[serde( flatten )]
+    pub extra: toml::Table// fields from other versions of the app, kept as they are
+}
+// Remote console of a server run from an install, so plugins can be reloaded on it after saving
+#[derive( Debug, Clone, serde::Serialize, serde::Deserialize )]
+#[serde( default )]
+pub struct RconSettings
+{
+    pub reload_on_save: bool,
+    pub host: String,
+    pub port: u16,
+    pub password: String,
+    #[serde( flatten )]
+    pub extra: toml::Table// fields from other versions of the app, kept as they are
+}
+
+impl Default for RconSettings
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            reload_on_save: false,
+            host: "127.0.0.1".to_string(),
+            port: 27015,
+            password: String::new(),
+            extra: toml::Table::new()
+        }
+    }
+}
+// A named Sven Co-op installation, e.g. the client, a dedicated server or a staging copy.
+// Everything kept per install lives in here, so installs don't share their list view or server.
+#[derive( Debug, Clone, serde::Serialize, serde::Deserialize )]
+pub struct Install
+{
+    pub name: String,
+    pub path: PathBuf,// the "svencoop" folder
+    #[serde( default )]
+    pub view: ViewPrefs,
+    #[serde( default )]
+    pub rcon: RconSettings,
+    #[serde( flatten )]
+    pub extra: toml::Table// fields from other versions of the app, kept as they are
+}
+
+impl Install
+{
+    pub fn new(name: &str, path: &Path) -> Self
+    {
+        Self
+        {
+            name: name.to_string(),
+            path: path.to_path_buf(),
+            view: ViewPrefs::default(),
+            rcon: RconSettings::default(),
+            extra: toml::Table::new()
+        }
+    }
+    // Named after the folder the game lives in, e.g. "Sven Co-op" or "Sven Co-op Dedicated Server"
+    pub fn default_name(path: &Path) -> String
+    {
+        path.parent()
+            .and_then( |p| p.file_name() )
+            .map( |n| n.to_string_lossy().into_owned() )
+        .unwrap_or( "Sven Co-op".to_string() )
+    }
+}
+// Bump this when the layout of the config changes, and add a step to MIGRATIONS taking the old layout to the new one
+pub const CONFIG_VERSION: i64 = 2;
+// MIGRATIONS[n] upgrades a version n + 1 config to version n + 2, working on the raw TOML
+const MIGRATIONS: &[fn(&mut toml::Table)] =
+&[
+    migrate_v1_installs
+];
 // struct only for housing serialised data
-#[derive( Debug, Default, serde::Serialize, serde::Deserialize )]
+#[derive( Debug, serde::Serialize, serde::Deserialize )]
 pub struct Config
 {
-    pub svencoopdir: Option<String>
+    pub version: i64,
+    pub active_install: Option<String>,
+    #[serde( default )]
+    pub settings: Settings,
+    #[serde( default )]
+    pub window: WindowPrefs,
+    #[serde( default )]
+    pub installs: Vec<Install>,
+    #[serde( flatten )]
+    pub extra: toml::Table// fields from other versions of the app, kept as they are
+}
+
+impl Default for Config
+{
+    fn default() -> Self
+    {
+        Self
+        {
+            version: CONFIG_VERSION,
+            active_install: None,
+            settings: Settings::default(),
+            window: WindowPrefs::default(),
+            installs: Vec::new(),
+            extra: toml::Table::new()
+        }
+    }
+}
+
+impl Config
+{
+    pub fn find_install(&self, name: &str) -> Option<&Install>
+    {
+        self.installs.iter().find( |i| i.name.eq_ignore_ascii_case( name ) )
+    }
+    // The install last used, or the first one if that has gone
+    pub fn active(&self) -> Option<&Install>
+    {
+        self.active_install
+            .as_deref()
+            .and_then( |name| self.find_install( name ) )
+        .or( self.installs.first() )
+    }
+    // Adds an install, the name is given a number on the end if it's already taken
+    pub fn add_install(&mut self, name: &str, path: &Path) -> Install
+    {
+        let mut unique = name.to_string();
+        let mut n = 2;
+
+        while self.find_install( &unique ).is_some()
+        {
+            unique = format!( "{} ({})", name, n );
+            n += 1;
+        }
+
+        let install = Install::new( &unique, path );
+        self.installs.push( install.clone() );
+
+        install
+    }
+}
+// Version 1 only knew about a single install, "svencoopdir"
+fn migrate_v1_installs(table: &mut toml::Table)
+{
+    let Some( toml::Value::String( dir ) ) = table.remove( "svencoopdir" ) else { return };
+
+    if table.get( "installs" ).and_then( toml::Value::as_array ).is_some_and( |a| !a.is_empty() )
+    {
+        return;
+    }
+
+    let path = PathBuf::from( dir );
+    let name = Install::default_name( &path );
+    let mut install = toml::Table::new();
+    install.insert( "name".into(), name.clone().into() );
+    install.insert( "path".into(), path.to_string_lossy().into_owned().into() );
+
+    table.insert( "installs".into(), toml::Value::Array( vec![toml::Value::Table( install )] ) );
+    table.insert( "active_install".into(), name.into() );
 }
 
 fn appdata_base() -> PathBuf 
@@ -81,63 +318,170 @@ fn config_path() -> PathBuf
     appdata_base().join( format!( "{}.toml", APPNAME ) )
 }
 
+// Why the config couldn't be used, pointing at the file so it can be fixed by hand
+fn unreadable(e: &dyn std::fmt::Display) -> io::Error
+{
+    io::Error::new( io::ErrorKind::InvalidData,
+        tr!( "The config '{}' could not be read, fix or remove it.\n{}", config_path().display(), e ) )
+}
+// Configs from before versioning don't have one
+fn version_of(table: &toml::Table) -> i64
+{
+    table.get( "version" ).and_then( toml::Value::as_integer ).unwrap_or( 1 )
+}
+
 pub fn read_store() -> Result<Config, io::Error>
 {
     match fs::read_to_string( config_path() )
     {
-        Ok( s ) => toml::from_str( &s ).map_err( io::Error::other ),
+        Ok( s ) =>
+        {
+            let mut table: toml::Table = toml::from_str( &s ).map_err( |e| unreadable( &e ) )?;
+            let version = version_of( &table );
+
+            if version < CONFIG_VERSION
+            {
+                migrate( &mut table, version, &s )?;
+            }
+
+            toml::Value::Table( table ).try_into().map_err( |e| unreadable( &e ) )
+        }
         // Forgot why I returned Config::default() here.
         Err( e ) if e.kind() == io::ErrorKind::NotFound => Ok( Config::default() ),
         Err( e ) => Err( e )
     }
 }
+// Runs the migration steps a config of `version` needs, in memory
+fn upgrade(table: &mut toml::Table, version: i64)
+{
+    for step in MIGRATIONS.iter().skip( ( version.max( 1 ) - 1 ) as usize )
+    {
+        step( table );
+    }
 
-fn write_store(st: &Config) -> Result<(), io::Error>
+    table.insert( "version".into(), CONFIG_VERSION.into() );
+}
+// Upgrades an old config to the current version and writes it back in place. The original is kept next to it first.
+// A config from a newer version of the app is never passed in here, it's read as is and write_store won't replace it.
+fn migrate(table: &mut toml::Table, version: i64, original: &str) -> Result<(), io::Error>
 {
+    let backup = config_path().with_extension( format!( "v{}.toml.bak", version ) );
+    utils::write_atomic( &backup, original.as_bytes() )?;
+    upgrade( table, version );
+
+    let s = toml::to_string_pretty( table ).map_err( io::Error::other )?;
+    utils::write_atomic( &config_path(), s.as_bytes() )
+}
+
+// The config on disk is only read, never written, if it can't be parsed or a newer version of the app wrote it.
+// Either way writing it would lose what is in it.
+pub fn write_store(st: &Config) -> Result<(), io::Error>
+{
+    if let Ok( existing ) = fs::read_to_string( config_path() )
+    {
+        let table: toml::Table = toml::from_str( &existing ).map_err( |e| unreadable( &e ) )?;
+
+        if version_of( &table ) > CONFIG_VERSION
+        {
+            return Err( io::Error::new( io::ErrorKind::PermissionDenied,
+                tr!( "The config '{}' is from a newer version of {}, it is left as it is. Update the app to change settings.",
+                config_path().display(), APPNAME ) ) );
+        }
+    }
+
     fs::create_dir_all( appdata_base() )?;
 
-    let p = config_path();
-    let buf = p.with_extension( "toml.tmp" );
     let s = toml::to_string_pretty( st ).map_err( io::Error::other )?;
 
-    fs::write( &buf, s.as_bytes() )?;
-    fs::rename( &buf, &p )?;
-    
-    Ok(())
+    utils::write_atomic( &config_path(), s.as_bytes() )
+}
+// A usable "svencoop" folder has the plugin file or the game's FGD in it
+pub fn is_game_dir(path: &Path) -> bool
+{
+    path.join( FILENAME_PLUGINS ).is_file() || path.join( "sven-coop.fgd" ).is_file()
 }
-// Returns the path to the plugins file
-pub fn init() -> Result<PathBuf, io::Error>
-{   // Load config first if its exists
-    if let Ok( store ) = read_store() && let Some( dir ) = store.svencoopdir
+// The "svencoop" folder of a folder picked or given as the game's: it can be that folder or the game's root folder
+pub fn resolve_svencoop_dir(dir: &Path) -> Option<PathBuf>
+{
+    [dir.to_path_buf(), dir.join( "svencoop" )].into_iter().find( |d| is_game_dir( d ) )
+}
+// Usual places for a dedicated server installed with SteamCMD or by hand
+fn common_server_dirs() -> Vec<PathBuf>
+{
+    let mut candidates = Vec::new();
+    let server = "Sven Co-op Dedicated Server";
+
+    #[cfg(target_os = "windows")]
     {
-        return Ok( PathBuf::from( dir ) );
+        for root in ["C:\\steamcmd", "C:\\SteamCMD", "C:\\Program Files (x86)\\steamcmd"]
+        {
+            candidates.push( PathBuf::from( root ).join( "steamapps" ).join( "common" ).join( server ) );
+        }
+
+        candidates.push( PathBuf::from( "C:\\svends" ) );
+        candidates.push( PathBuf::from( "C:\\" ).join( server ) );
     }
-    // Initial setup
-    #[cfg( target_os = "windows" )] crate::utils::open_terminal();
-    println!( "Initial setup, please wait..." );
 
-    let exe_path = env::current_dir().unwrap_or( PathBuf::from( "." ) );// If the plugin file exists in the current dir, just use that.
-    let svencoop_dir =
-    match exe_path.join( FILENAME_PLUGINS ).exists()
+    #[cfg(target_os = "linux")]
     {
-        true => exe_path,
-        false =>// Doesn't exist, look for it
+        if let Some( home ) = dirs::home_dir()
         {
-            utils::search_drives( "sven-coop.fgd" )
-                .unwrap_or( PathBuf::from( "." ) )
-                .with_extension( "" )// drops the extension
+            candidates.push( home.join( "Steam/steamapps/common" ).join( server ) );
+            candidates.push( home.join( "steamcmd/steamapps/common" ).join( server ) );
+            candidates.push( home.join( "svends" ) );
+            candidates.push( home.join( server ) );
         }
-    };
 
-    if !svencoop_dir.exists()
+        for root in ["/opt", "/srv", "/home/steam"]
+        {
+            candidates.push( PathBuf::from( root ).join( "svends" ) );
+            candidates.push( PathBuf::from( root ).join( server ) );
+        }
+
+        candidates.push( PathBuf::from( "/home/steam/Steam/steamapps/common" ).join( server ) );
+    }
+
+    candidates.into_iter().map( |d| d.join( "svencoop" ) ).collect()
+}
+// Looks for Sven Co-op installs: Steam libraries first, then the usual dedicated server locations.
+// Walking the drives is slow so it's only done when neither of those turned anything up.
+// `progress` is told where is being looked at, `found` gets each "svencoop" folder once. Setting `cancel` stops the search.
+pub fn discover_installs<P, F>(cancel: &AtomicBool, mut progress: P, mut found: F)
+where P: FnMut(&Path), F: FnMut(&Path)
+{
+    let mut seen: Vec<PathBuf> = Vec::new();
+
+    for dir in steam::find_svencoop_dirs().into_iter().chain( common_server_dirs() )
     {
-        let s_err = "No directory to svencoop exists.";
-        eprintln!( "{}", s_err );
-        #[cfg( target_os = "windows" )] crate::utils::close_terminal();
+        if cancel.load( Ordering::Relaxed )
+        {
+            return;
+        }
+
+        progress( &dir );
 
-        return Err( io::Error::new( io::ErrorKind::NotFound, s_err ) );
+        if is_game_dir( &dir ) && !seen.contains( &dir )
+        {
+            found( &dir );
+            seen.push( dir );
+        }
+    }
+
+    if seen.is_empty()
+    {
+        utils::walk_drives( "sven-coop.fgd", cancel, progress, |fgd|
+        {
+            if let Some( dir ) = fgd.parent() && !seen.iter().any( |s| s == dir )
+            {
+                found( dir );
+                seen.push( dir.to_path_buf() );
+            }
+        });
     }
-    // Ensure plugin files exist - redundant for default_plugins.txt if the app was directly installed
+}
+// Ensure plugin files exist - redundant for default_plugins.txt if the app was directly installed
+pub fn ensure_plugin_files(svencoop_dir: &Path) -> Result<(), io::Error>
+{
     let enabled_file = svencoop_dir.join( FILENAME_PLUGINS );
     let disabled_file = svencoop_dir.join( FILENAME_DISABLED_PLUGINS );
     
@@ -150,10 +494,225 @@ pub fn init() -> Result<PathBuf, io::Error>
     {
         fs::write( &disabled_file, b"" )?;
     }
-    // Save folder path into TOML
-    write_store( &Config { svencoopdir: Some( svencoop_dir.to_string_lossy().into_owned() ) } )?;
+
+    Ok(())
+}
+// Returns the install to manage - the one named, otherwise the last one used. Its folder is checked every time.
+// `setup` is run to find a folder when there is no install yet, or when the stored one has gone stale. It's given a notice
+// to show and the other saved installs to offer, and returns the folder chosen first followed by any others it found.
+pub fn init<S>(install_name: Option<&str>, setup: S) -> Result<Install, io::Error>
+where S: FnOnce(&str, Vec<PathBuf>) -> Vec<PathBuf>
+{   // Load config first if its exists. One that can't be read is left for the user to fix rather than replaced.
+    let mut store = read_store()?;
+
+    let requested =
+    match install_name
+    {
+        Some( name ) => Some( store.find_install( name ).cloned().ok_or_else( ||
+        {
+            let names: Vec<_> = store.installs.iter().map( |i| i.name.as_str() ).collect();
+            io::Error::new( io::ErrorKind::NotFound,
+                tr!( "No install named '{}'. Known installs: {}", name, names.join( ", " ) ) )
+        })? ),
+        None => store.active().cloned()
+    };
+
+    if let Some( install ) = requested
+    {
+        if is_game_dir( &install.path )
+        {
+            return Ok( install );
+        }
+        // The game was moved or uninstalled since last time
+        let others: Vec<PathBuf> = store.installs
+            .iter()
+            .filter( |i| i.path != install.path && is_game_dir( &i.path ) )
+            .map( |i| i.path.clone() )
+        .collect();
+
+        let notice = tr!( "The install '{}' could not be found at '{}'. It may have been moved or uninstalled.\n\
+            Search for it again, browse to where it is now, or pick another saved install.",
+            install.name, install.path.display() );
+
+        let Some( chosen ) = setup( &notice, others ).into_iter().next() else
+        {
+            return Err( io::Error::new( io::ErrorKind::NotFound,
+                tr!( "The install '{}' is missing from '{}'.", install.name, install.path.display() ) ) );
+        };
+
+        ensure_plugin_files( &chosen )?;
+
+        let recovered =
+        match store.installs.iter().find( |i| i.path == chosen ).cloned()
+        {
+            Some( other ) => other,
+            None =>
+            {   // Somewhere new, so that's where the stale install lives now
+                let mut moved = install.clone();
+                moved.path = chosen;
+
+                if let Some( stale ) = store.installs.iter_mut().find( |i| i.name == install.name )
+                {
+                    *stale = moved.clone();
+                }
+
+                moved
+            }
+        };
+
+        store.active_install = Some( recovered.name.clone() );
+        write_store( &store )?;
+
+        return Ok( recovered );
+    }
+    // Initial setup
+    let exe_path = env::current_dir().unwrap_or( PathBuf::from( "." ) );// If the plugin file exists in the current dir, just use that.
+    let found =
+    match exe_path.join( FILENAME_PLUGINS ).exists()
+    {
+        true => vec![exe_path],
+        false => setup( "", Vec::new() )// Doesn't exist, look for it
+    };
+
+    let Some( svencoop_dir ) = found.first().cloned() else
+    {
+        return Err( io::Error::new( io::ErrorKind::NotFound, tr!( "No directory to svencoop was chosen." ) ) );
+    };
+
+    ensure_plugin_files( &svencoop_dir )?;
+    // Save folder paths into TOML, the one chosen is used. Others (e.g. a dedicated server next to the client) are kept for switching to.
+    let install = store.add_install( &Install::default_name( &svencoop_dir ), &svencoop_dir );
+    store.active_install = Some( install.name.clone() );
+
+    for dir in found.iter().skip( 1 ).filter( |d| **d != svencoop_dir )
+    {
+        store.add_install( &Install::default_name( dir ), dir );
+    }
+
+    write_store( &store )?;
     println!( "Sven Co-op path found: {}", svencoop_dir.to_string_lossy() );
-    #[cfg( target_os = "windows" )] crate::utils::close_terminal();
 
-    Ok( svencoop_dir )
+    Ok( install )
+}
+// An install given on the command line or through SCPM_GAME_DIR, used instead of the stored ones.
+// The config is left alone unless `remember` is set, in which case it's saved and becomes the active install.
+pub fn init_override(dir: &Path, remember: bool) -> Result<Install, io::Error>
+{
+    let Some( svencoop_dir ) = resolve_svencoop_dir( dir ) else
+    {
+        return Err( io::Error::new( io::ErrorKind::InvalidInput,
+            tr!( "'{}' is not a Sven Co-op game folder, it must contain '{}' or 'sven-coop.fgd'.",
+            dir.display(), FILENAME_PLUGINS ) ) );
+    };
+
+    ensure_plugin_files( &svencoop_dir )?;
+    // Not even read without `remember`, reading it could migrate it and one that can't be read shouldn't stop the override
+    if !remember
+    {
+        return Ok( Install::new( &Install::default_name( &svencoop_dir ), &svencoop_dir ) );
+    }
+
+    let mut store = read_store()?;
+    let install =
+    match store.installs.iter().find( |i| i.path == svencoop_dir )
+    {
+        Some( existing ) => existing.clone(),
+        None => store.add_install( &Install::default_name( &svencoop_dir ), &svencoop_dir )
+    };
+
+    store.active_install = Some( install.name.clone() );
+    write_store( &store )?;
+
+    Ok( install )
+}
+// Remembers which install was used last, so it's opened by default next time
+pub fn set_active_install(name: &str) -> Result<(), io::Error>
+{
+    let mut store = read_store()?;
+    store.active_install = Some( name.to_string() );
+
+    write_store( &store )
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn upgraded(text: &str) -> Config
+    {
+        let mut table: toml::Table = toml::from_str( text ).unwrap();
+        let version = version_of( &table );
+        upgrade( &mut table, version );
+
+        toml::Value::Table( table ).try_into().unwrap()
+    }
+
+    #[test]
+    fn migrate_moves_a_v1_folder_into_the_install_list()
+    {
+        let config = upgraded( "svencoopdir = \"/games/Sven Co-op/svencoop\"\n" );
+
+        assert_eq!( config.version, CONFIG_VERSION );
+        assert_eq!( config.installs.len(), 1 );
+        assert_eq!( config.installs[0].name, "Sven Co-op" );
+        assert_eq!( config.installs[0].path, PathBuf::from( "/games/Sven Co-op/svencoop" ) );
+        assert_eq!( config.active_install.as_deref(), Some( "Sven Co-op" ) );
+        assert!( !config.extra.contains_key( "svencoopdir" ) );
+    }
+
+    #[test]
+    fn migrate_keeps_keys_it_does_not_know()
+    {
+        let config = upgraded( "svencoopdir = \"/sc/svencoop\"\nfrom_elsewhere = 3\n\n[future]\nflag = true\n" );
+        let written = toml::to_string_pretty( &config ).unwrap();
+
+        assert_eq!( config.extra.get( "from_elsewhere" ).and_then( toml::Value::as_integer ), Some( 3 ) );
+        assert!( written.contains( "from_elsewhere = 3" ) );
+        assert!( written.contains( "[future]" ) );
+    }
+
+    #[test]
+    fn migrate_leaves_a_current_config_as_it_is()
+    {
+        let text = "version = 2\nactive_install = \"Server\"\n\n[[installs]]\nname = \"Server\"\npath = \"/srv/svencoop\"\n";
+        let config = upgraded( text );
+
+        assert_eq!( config.installs.len(), 1 );
+        assert_eq!( config.active_install.as_deref(), Some( "Server" ) );
+    }
+    #[test]
+    fn nested_tables_keep_keys_they_do_not_know()
+    {
+        let text = "version = 2
+
+[settings]
+theme = \"dark\"\nfont_size = 12
+
+[window]
+width = 800
+maximised = true
+
+            [[installs]]
+name = \"Server\"
+path = \"/srv/svencoop\"
+
+[installs.view]
+grouped = true
+
+[installs.rcon]
+port = 27016
+timeout = 5
+";
+        let config = upgraded( text );
+        let written = toml::to_string_pretty( &config ).unwrap();
+        let reread: Config = toml::from_str( &written ).unwrap();
+
+        assert_eq!( reread.settings.extra.get( "font_size" ).and_then( toml::Value::as_integer ), Some( 12 ) );
+        assert_eq!( reread.window.extra.get( "maximised" ).and_then( toml::Value::as_bool ), Some( true ) );
+        assert_eq!( reread.installs[0].view.extra.get( "grouped" ).and_then( toml::Value::as_bool ), Some( true ) );
+        assert_eq!( reread.installs[0].rcon.extra.get( "timeout" ).and_then( toml::Value::as_integer ), Some( 5 ) );
+        assert_eq!( reread.installs[0].rcon.port, 27016 );
+        assert_eq!( reread.window.width, Some( 800 ) );
+    }
 }
@@ -15,29 +15,64 @@ GNU General Public License for more details.
 You should have received a copy of the GNU General Public License
 along with this program. If not, see <https://www.gnu.org/licenses/>.
 */
-use std::{collections, env, fs, io, path::Path};
+use std::{collections, env, io, path::{Path, PathBuf}};
 
 use crate::{
-    app::{launch_gui, popup, PopupButtons},
-    config,
+    app::{launch_gui, popup, theme, wizard::run_setup_wizard, PopupButtons},
+    cli, config,
     config::SVENCOOP_PATH,
     plugin::{
-        load_plugins, save_plugins, PluginContext, PluginEntry, PluginState,
+        load_plugin_file, recover_plugin_files, save_plugins, DiskSnapshot, PluginContext, PluginEntry, PluginState,
         FILENAME_DISABLED_PLUGINS, FILENAME_PLUGINS, SCRIPT_EXT,
     },
+    i18n, tr, trn,
     APPNAME,
 };
 
 pub fn run() -> Result<(), io::Error>
 {
+    let settings = config::read_store().unwrap_or_default().settings;
+    i18n::set_language( &settings.language );
+    theme::set_theme( settings.theme );
+
+    let args =
+    match cli::parse( env::args().skip( 1 ) )
+    {
+        Ok( args ) => args,
+        Err( e ) =>
+        {
+            popup( &tr!( "Invalid Arguments" ), &e, "❌", PopupButtons::Ok, |_| { } );
+            return Err( io::Error::new( io::ErrorKind::InvalidInput, e ) );
+        }
+    };
+
+    let setup = |notice: &str, known|
+    {
+        run_setup_wizard( notice, known ).unwrap_or_else( |e|
+        {
+            eprintln!( "Failed to open the setup window: {}", e );
+            Vec::new()
+        })
+    };
+
+    // A folder given on the command line or in the environment takes priority over the stored installs
+    let game_dir = args.game_dir.clone().or_else( || env::var_os( config::ENV_GAME_DIR ).map( PathBuf::from ) );
+
+    let install =
+    match game_dir
+    {
+        Some( dir ) => config::init_override( &dir, args.remember ),
+        None => config::init( args.install.as_deref(), setup )
+    };
+
     let svencoop_dir =
-    match config::init()
+    match install
     {
-        Ok( dir ) => dir,
+        Ok( install ) => install.path,
         Err( e ) =>
         {
-            popup( "Sven Co-op install Not Found",
-                &format!( "Could not find a valid Sven Co-op installation.\nReason:\n{}\n\n\
+            popup( &tr!( "Sven Co-op install Not Found" ),
+                &tr!( "Could not find a valid Sven Co-op installation.\nReason:\n{}\n\n\
                 Try installing {} directly to 'Sven Co-op\\svencoop' and try again.",
                 e, APPNAME ),
                 "❌",
@@ -56,13 +91,14 @@ pub fn run() -> Result<(), io::Error>
 
     let mut plugins = collections::HashMap::new();
 
-    SVENCOOP_PATH.set( svencoop_dir.clone() ).unwrap_or_default();
+    SVENCOOP_PATH.set( svencoop_dir.clone() );
+    recover_plugin_files( &svencoop_dir );
 
     for (path, state) in files
     {
-        match fs::read_to_string( path.to_str().unwrap_or_default() )
+        match load_plugin_file( &path, state )
         {
-            Ok( file ) => plugins.extend( load_plugins( &file, state ) ),
+            Ok( loaded ) => plugins.extend( loaded ),
 
             Err( e ) =>
             {
@@ -71,8 +107,8 @@ pub fn run() -> Result<(), io::Error>
                     break;
                 }
 
-                popup( "Error reading plugin file",
-                    &format!( "The plugin file in '{}' could not be opened.\n\
+                popup( &tr!( "Error reading plugin file" ),
+                    &tr!( "The plugin file in '{}' could not be opened.\n\
                     Reason:\n{}",
                     path.display(),
                     e ),
@@ -83,13 +119,29 @@ pub fn run() -> Result<(), io::Error>
         }
     }
 
-    let args: Vec<_> = env::args().collect();
+    if let Some( names ) = &args.show
+    {
+        let ( text, unknown ) = cli::show_plugins( &svencoop_dir, &plugins, names );
+        print!( "{}", text );
 
-    match args.len()
+        for name in &unknown
+        {
+            eprintln!( "{}", tr!( "No plugin named '{}'", name ) );
+        }
+
+        return match unknown.is_empty()
+        {
+            true => Ok( () ),
+            false => Err( io::Error::new( io::ErrorKind::NotFound,
+                trn!( "{} of the plugins asked for was not found", "{} of the plugins asked for were not found", unknown.len() ) ) )
+        };
+    }
+
+    match args.files.is_empty()
     {
-        n if n > 1 =>
+        false =>
         {
-            for file in &args[1..]
+            for file in &args.files
             {
                 if file.is_empty()
                 {
@@ -98,8 +150,8 @@ pub fn run() -> Result<(), io::Error>
 
                 if !file.ends_with(SCRIPT_EXT)
                 {
-                    popup( "Invalid File",
-                        &format!(
+                    popup( &tr!( "Invalid File" ),
+                        &tr!(
                         "'{}' is not a valid plugin script file.\n\
                         Plugin script files end with the '.as' file extension.",
                         file ),
@@ -117,8 +169,8 @@ pub fn run() -> Result<(), io::Error>
 
                 if name.is_empty()
                 {
-                    popup( "Error",
-                        &format!( "Failed to extract plugin name from file '{}'", file ),
+                    popup( &tr!( "Error" ),
+                        &tr!( "Failed to extract plugin name from file '{}'", file ),
                         "❌",
                         PopupButtons::Ok,
                         |_| { } );
@@ -128,8 +180,8 @@ pub fn run() -> Result<(), io::Error>
 
                 if plugins.clone().contains_key( name )
                 {
-                    popup( "Info",
-                        &format!( "The plugin script '{}' is already installed.\n\n\
+                    popup( &tr!( "Info" ),
+                        &tr!( "The plugin script '{}' is already installed.\n\n\
                         To disable or remove this plugin, launch {} and do this manually.",
                         file, APPNAME ),
                         "ℹ️",
@@ -144,8 +196,8 @@ pub fn run() -> Result<(), io::Error>
 
                 if let Err( e ) = PluginEntry::install_plugin( file, &svencoop_dir )
                 {
-                    popup( "Installation Failed",
-                           &format!( "Failed to install plugin '{}' from script file '{}'.\n\
+                    popup( &tr!( "Installation Failed" ),
+                           &tr!( "Failed to install plugin '{}' from script file '{}'.\n\
                            Error code {}",
                            name, file, e ),
                            "❌",
@@ -159,8 +211,8 @@ pub fn run() -> Result<(), io::Error>
                 {
                     Ok( _ ) =>
                     {
-                        popup( "Plugin Installed",
-                            &format!( "Plugin '{}' installed from script file '{}'.",
+                        popup( &tr!( "Plugin Installed" ),
+                            &tr!( "Plugin '{}' installed from script file '{}'.",
                             name, file ),
                             "ℹ️",
                             PopupButtons::Ok,
@@ -169,8 +221,8 @@ pub fn run() -> Result<(), io::Error>
 
                     Err( e ) =>
                     {
-                        popup( "Installation Failed",
-                            &format!(
+                        popup( &tr!( "Installation Failed" ),
+                            &tr!(
                             "Failed to install plugin '{}' from script file '{}'.\n\
                             Error code {}",
                             name, file, e ),
@@ -182,12 +234,15 @@ pub fn run() -> Result<(), io::Error>
             }
         }
 
-        _ =>
+        true =>
         {
-            if let Err( e ) = launch_gui( PluginContext::from_hashmap( plugins ) )
+            let mut ctx = PluginContext::from_hashmap( plugins );
+            ctx.disk = DiskSnapshot::capture( &svencoop_dir );
+
+            if let Err( e ) = launch_gui( ctx )
             {
-                popup( "Error",
-                    &format!( "Failed to launch window.\nReason: {e}" ),
+                popup( &tr!( "Error" ),
+                    &tr!( "Failed to launch window.\nReason: {}", e ),
                     "❌",
                     PopupButtons::None,
                     |_| { } );
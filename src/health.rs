@@ -0,0 +1,380 @@
+/*
+	Sven Co-op Plugin Manager Version 2.0
+
+Copyright (C) 2025 Outerbeast
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program. If not, see <https://www.gnu.org/licenses/>.
+*/
+use std::
+{
+    fs,
+    collections::{ HashMap, HashSet },
+    hash::{ DefaultHasher, Hash, Hasher },
+    path::{ Path, PathBuf },
+    sync::{ Arc, Mutex, atomic::{ AtomicBool, Ordering } },
+    thread,
+    time::Duration
+};
+
+use crate::
+{
+    compile_log::{ self, CompileError },
+    config::SVENCOOP_PATH,
+    plugin::{ PLUGINS_DIR, SCRIPT_EXT, addon_plugins_dir },
+    tr
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs( 3 );
+
+// Where a plugin's script was found, and whether it looks like it will load
+#[derive( Debug, Clone, PartialEq )]
+pub enum ScriptHealth
+{
+    Base( PathBuf ),// svencoop/scripts/plugins
+    Addon( PathBuf ),// svencoop_addon/scripts/plugins
+    Shadowed { used: PathBuf, hidden: PathBuf },// in both, the addon copy is the one the game loads
+    Missing,
+    Lint { path: PathBuf, problem: String }
+}
+
+impl ScriptHealth
+{
+    pub fn check(svencoop_dir: &Path, script: &str) -> Self
+    {
+        let base = svencoop_dir.join( PLUGINS_DIR ).join( script ).with_extension( SCRIPT_EXT );
+        let addon = addon_plugins_dir( svencoop_dir ).join( script ).with_extension( SCRIPT_EXT );
+        // The game searches svencoop_addon before svencoop
+        let found =
+        match ( addon.is_file(), base.is_file() )
+        {
+            ( true, true ) => ScriptHealth::Shadowed { used: addon, hidden: base },
+            ( true, false ) => ScriptHealth::Addon( addon ),
+            ( false, true ) => ScriptHealth::Base( base ),
+            ( false, false ) => return ScriptHealth::Missing
+        };
+
+        let path = found.path().map( Path::to_path_buf ).unwrap_or_default();
+
+        match fs::read( &path ).map( |bytes| lint_script( &String::from_utf8_lossy( &bytes ) ) )
+        {
+            Ok( None ) => found,
+            Ok( Some( problem ) ) => ScriptHealth::Lint { path, problem },
+            Err( e ) => ScriptHealth::Lint { path, problem: tr!( "Could not be read: {}", e ) }
+        }
+    }
+    // The file the game would load
+    pub fn path(&self) -> Option<&Path>
+    {
+        match self
+        {
+            ScriptHealth::Base( path )
+            | ScriptHealth::Addon( path )
+            | ScriptHealth::Shadowed { used: path, .. }
+            | ScriptHealth::Lint { path, .. } => Some( path ),
+            ScriptHealth::Missing => None
+        }
+    }
+
+    pub fn describe(&self) -> String
+    {
+        match self
+        {
+            ScriptHealth::Base( path ) | ScriptHealth::Addon( path ) => path.display().to_string(),
+            ScriptHealth::Shadowed { used, hidden } =>
+                tr!( "{} - hides {}", used.display(), hidden.display() ),
+            ScriptHealth::Missing => tr!( "Script file not found in svencoop or svencoop_addon" ),
+            ScriptHealth::Lint { path, problem } => format!( "{} - {}", path.display(), problem )
+        }
+    }
+}
+// Quick sanity checks on a plugin script, returns what is wrong with it if anything.
+// This is no compiler, it only catches files that can't possibly load.
+pub fn lint_script(source: &str) -> Option<String>
+{
+    if source.trim().is_empty()
+    {
+        return Some( tr!( "The script file is empty" ) );
+    }
+
+    let code = strip_comments_and_strings( source );
+    let mut depth = [0i32; 2];
+
+    for c in code.chars()
+    {
+        match c
+        {
+            '{' => depth[0] += 1,
+            '}' => depth[0] -= 1,
+            '(' => depth[1] += 1,
+            ')' => depth[1] -= 1,
+            _ => continue
+        }
+
+        if depth.iter().any( |d| *d < 0 )
+        {
+            break;
+        }
+    }
+
+    if depth[0] != 0
+    {
+        return Some( tr!( "Unbalanced braces {{ }}" ) );
+    }
+
+    if depth[1] != 0
+    {
+        return Some( tr!( "Unbalanced parentheses ( )" ) );
+    }
+
+    if !code.contains( "PluginInit" )
+    {
+        return Some( tr!( "No PluginInit() function, the game will not load it as a plugin" ) );
+    }
+
+    None
+}
+// Blanks out comments and string literals so brackets inside them aren't counted
+fn strip_comments_and_strings(source: &str) -> String
+{
+    let mut out = String::with_capacity( source.len() );
+    let mut chars = source.chars().peekable();
+
+    while let Some( c ) = chars.next()
+    {
+        match c
+        {
+            '/' if chars.peek() == Some( &'/' ) =>
+            {
+                for c in chars.by_ref()
+                {
+                    if c == '\n'
+                    {
+                        out.push( '\n' );
+                        break;
+                    }
+                }
+            }
+
+            '/' if chars.peek() == Some( &'*' ) =>
+            {
+                chars.next();
+                let mut last = '\0';
+
+                for c in chars.by_ref()
+                {
+                    if last == '*' && c == '/'
+                    {
+                        break;
+                    }
+
+                    last = c;
+                }
+
+                out.push( ' ' );
+            }
+
+            '"' | '\'' =>
+            {
+                let mut escaped = false;
+
+                for s in chars.by_ref()
+                {
+                    match s
+                    {
+                        _ if escaped => escaped = false,
+                        '\\' => escaped = true,
+                        _ if s == c => break,
+                        _ => {}
+                    }
+                }
+
+                out.push( ' ' );
+            }
+
+            _ => out.push( c )
+        }
+    }
+
+    out
+}
+// Changes whenever one of the files a script's result depends on is added, removed or written to:
+// the script in either plugin folder, and the includes it had when it was last checked
+fn script_stamp(svencoop_dir: &Path, script: &str, includes: &[(PathBuf, String)]) -> u64
+{
+    let mut hasher = DefaultHasher::new();
+    let candidates = [svencoop_dir.join( PLUGINS_DIR ), addon_plugins_dir( svencoop_dir )].map( |d| d.join( script ).with_extension( SCRIPT_EXT ) );
+
+    for path in candidates.iter().chain( includes.iter().map( |(p, _)| p ) )
+    {
+        let meta = fs::metadata( path ).ok();
+        ( path, meta.as_ref().and_then( |m| m.modified().ok() ), meta.map( |m| m.len() ) ).hash( &mut hasher );
+    }
+
+    hasher.finish()
+}
+// What the background thread knows about a script since it last checked it
+struct Checked
+{
+    stamp: u64,
+    files: Vec<(PathBuf, String)>// the script and its includes, as (path on disk, name in the logs)
+}
+
+#[derive( Debug, Default )]
+struct MonitorState
+{
+    dir: Option<PathBuf>,// the svencoop folder the results are for
+    scripts: HashSet<String>,
+    results: HashMap<String, ScriptHealth>,
+    errors: HashMap<String, Vec<CompileError>>// compiler errors from the server logs, only scripts that have any
+}
+// Keeps the health of every tracked script up to date on a background thread. A script is checked again when
+// one of its files changes, and the server logs are only read again when they change.
+#[derive( Debug, Default, Clone )]
+pub struct HealthMonitor
+{
+    state: Arc<Mutex<MonitorState>>,
+    stop: Arc<AtomicBool>
+}
+
+impl HealthMonitor
+{   // on_change is called from the background thread after new results are in
+    pub fn start<F>(&self, on_change: F)
+    where F: Fn() + Send + 'static
+    {
+        let state = self.state.clone();
+        let stop = self.stop.clone();
+
+        thread::spawn( move ||
+        {
+            let mut last_dir = None;
+            let mut last_logs = None;
+            let mut logged = Vec::new();
+            let mut checked: HashMap<String, Checked> = HashMap::new();
+            let mut wait = Duration::ZERO;// the first check goes straight away
+
+            while !stop.load( Ordering::Relaxed )
+            {
+                thread::sleep( wait );
+                wait = POLL_INTERVAL;
+
+                let Some( svencoop_dir ) = SVENCOOP_PATH.get() else { continue };
+                let Ok( ( scripts, results ) ) = state.lock().map( |s| ( s.scripts.clone(), s.results.keys().cloned().collect::<HashSet<_>>() ) ) else { continue };
+
+                if last_dir.as_ref() != Some( &svencoop_dir )
+                {
+                    checked.clear();
+                    last_logs = None;
+                    last_dir = Some( svencoop_dir.clone() );
+                }
+
+                checked.retain( |s, _| scripts.contains( s ) );
+                // The logs are only read again when one is added or written to
+                let logs = compile_log::logs_signature( &svencoop_dir );
+                let logs_changed = last_logs != Some( logs );
+
+                if logs_changed
+                {
+                    logged = compile_log::read_compile_errors( &svencoop_dir );
+                    last_logs = Some( logs );
+                }
+                // Only scripts whose files moved on, or that have no result yet, are checked again
+                let changed: Vec<&String> = scripts
+                    .iter()
+                    .filter( |s| !results.contains( *s ) || checked.get( *s ).is_none_or( |c| c.stamp != script_stamp( &svencoop_dir, s, &c.files ) ) )
+                .collect();
+
+                if changed.is_empty() && !logs_changed
+                {
+                    continue;
+                }
+
+                let mut health = Vec::new();
+
+                for script in &changed
+                {
+                    let files = compile_log::script_files( &svencoop_dir, script );
+                    let stamp = script_stamp( &svencoop_dir, script, &files );
+
+                    health.push( ( (*script).clone(), ScriptHealth::check( &svencoop_dir, script ) ) );
+                    checked.insert( (*script).clone(), Checked { stamp, files } );
+                }
+
+                let errors = checked
+                    .iter()
+                    .filter( |(s, _)| logs_changed || changed.contains( s ) )
+                    .map( |(s, c)| ( s.clone(), compile_log::errors_in_files( &c.files, &logged ) ) )
+                .collect::<Vec<_>>();
+
+                let Ok( mut state ) = state.lock() else { continue };
+                // Reset for another install while this was being checked, so these results are for the wrong one
+                if SVENCOOP_PATH.get().as_ref() != Some( &svencoop_dir ) || state.dir.as_ref().is_some_and( |d| *d != svencoop_dir )
+                {
+                    last_dir = None;
+                    continue;
+                }
+
+                state.dir = Some( svencoop_dir );
+                state.results.extend( health );
+                state.results.retain( |s, _| scripts.contains( s ) );
+
+                for ( script, found ) in errors
+                {
+                    match found.is_empty()
+                    {
+                        true => state.errors.remove( &script ),
+                        false => state.errors.insert( script, found )
+                    };
+                }
+
+                state.errors.retain( |s, _| scripts.contains( s ) );
+                drop( state );
+
+                on_change();
+            }
+        });
+    }
+
+    pub fn stop(&self)
+    {
+        self.stop.store( true, Ordering::Relaxed );
+    }
+    // Scripts to keep checking, anything not in here is dropped
+    pub fn track<'a, I>(&self, scripts: I)
+    where I: IntoIterator<Item = &'a str>
+    {
+        if let Ok( mut state ) = self.state.lock()
+        {
+            state.scripts = scripts.into_iter().map( str::to_string ).collect();
+        }
+    }
+    // None until the background thread has got to it
+    pub fn get(&self, script: &str) -> Option<ScriptHealth>
+    {
+        self.state.lock().ok()?.results.get( script ).cloned()
+    }
+    // Compiler errors logged for the script or anything it includes, empty when there are none
+    pub fn errors(&self, script: &str) -> Vec<CompileError>
+    {
+        self.state.lock().ok().and_then( |s| s.errors.get( script ).cloned() ).unwrap_or_default()
+    }
+    // Forgets every result, e.g. when switching to another install. Call it after SVENCOOP_PATH is set to the new one.
+    pub fn reset(&self)
+    {
+        if let Ok( mut state ) = self.state.lock()
+        {
+            state.dir = SVENCOOP_PATH.get();
+            state.results.clear();
+            state.errors.clear();
+        }
+    }
+}
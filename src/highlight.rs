@@ -0,0 +1,205 @@
+/*
+	Sven Co-op Plugin Manager Version 2.0
+
+Copyright (C) 2025 Outerbeast
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program. If not, see <https://www.gnu.org/licenses/>.
+*/
+// Splits AngelScript source into coloured pieces for the script viewer
+pub const TAB_WIDTH: usize = 4;
+
+const KEYWORDS: &[&str] =
+&[
+    "and", "abstract", "break", "case", "cast", "class", "const", "continue", "default", "do", "else",
+    "enum", "explicit", "external", "false", "final", "for", "from", "funcdef", "function", "get", "if",
+    "import", "in", "inout", "interface", "is", "mixin", "namespace", "not", "null", "or", "out",
+    "override", "private", "property", "protected", "return", "set", "shared", "super", "switch", "this",
+    "true", "typedef", "while", "xor"
+];
+
+const TYPES: &[&str] =
+&[
+    "auto", "bool", "double", "float", "int", "int8", "int16", "int32", "int64", "uint", "uint8",
+    "uint16", "uint32", "uint64", "void", "string", "array", "dictionary", "handle"
+];
+
+#[derive( Debug, Clone, Copy, PartialEq, Eq )]
+#[repr(i32)]
+pub enum TokenKind
+{
+    Plain = 0,
+    Keyword,
+    Type,
+    String,
+    Comment,
+    Number,
+    Preprocessor
+}
+
+// A source line broken into pieces to colour
+pub type HighlightedLine = Vec<(TokenKind, String)>;
+
+// Every line of the source, in order. Block comments carry over from one line to the next.
+pub fn highlight(source: &str) -> Vec<HighlightedLine>
+{
+    let mut in_comment = false;
+
+    source
+        .lines()
+        .map( |line| highlight_line( &expand_tabs( line ), &mut in_comment ) )
+    .collect()
+}
+
+fn expand_tabs(line: &str) -> String
+{
+    let mut out = String::with_capacity( line.len() );
+
+    for c in line.chars()
+    {
+        match c
+        {
+            '\t' => out.extend( std::iter::repeat_n( ' ', TAB_WIDTH - out.chars().count() % TAB_WIDTH ) ),
+            _ => out.push( c )
+        }
+    }
+
+    out
+}
+
+fn highlight_line(line: &str, in_comment: &mut bool) -> HighlightedLine
+{
+    let chars: Vec<char> = line.chars().collect();
+    let mut tokens: HighlightedLine = Vec::new();
+    let mut push = |kind: TokenKind, text: &[char]|
+    {
+        let text: String = text.iter().collect();
+
+        match tokens.last_mut()
+        {
+            Some( ( k, t ) ) if *k == kind => t.push_str( &text ),
+            _ => tokens.push( ( kind, text ) )
+        }
+    };
+
+    let mut i = 0;
+
+    while i < chars.len()
+    {
+        let start = i;
+        let c = chars[i];
+        let next = chars.get( i + 1 ).copied();
+
+        let kind =
+        if *in_comment
+        {
+            continue_comment( &chars, &mut i, in_comment );
+            TokenKind::Comment
+        }
+        else if c == '/' && next == Some( '/' )
+        {
+            i = chars.len();
+            TokenKind::Comment
+        }
+        else if c == '/' && next == Some( '*' )
+        {
+            *in_comment = true;
+            i += 2;
+            continue_comment( &chars, &mut i, in_comment );
+            TokenKind::Comment
+        }
+        else if c == '"' || c == '\''
+        {
+            i += 1;
+
+            while i < chars.len() && chars[i] != c
+            {
+                i += if chars[i] == '\\' { 2 } else { 1 };
+            }
+
+            i = ( i + 1 ).min( chars.len() );
+            TokenKind::String
+        }
+        else if c == '#' && chars[..i].iter().all( |c| c.is_whitespace() )
+        {
+            while i < chars.len() && ( i == start || chars[i].is_alphanumeric() )
+            {
+                i += 1;
+            }
+
+            TokenKind::Preprocessor
+        }
+        else if c.is_ascii_digit()
+        {
+            while i < chars.len() && ( chars[i].is_ascii_alphanumeric() || chars[i] == '.' )
+            {
+                i += 1;
+            }
+
+            TokenKind::Number
+        }
+        else if c.is_alphabetic() || c == '_'
+        {
+            while i < chars.len() && ( chars[i].is_alphanumeric() || chars[i] == '_' )
+            {
+                i += 1;
+            }
+
+            let word: String = chars[start..i].iter().collect();
+
+            if KEYWORDS.contains( &word.as_str() )
+            {
+                TokenKind::Keyword
+            }
+            else if TYPES.contains( &word.as_str() )
+            {
+                TokenKind::Type
+            }
+            else
+            {
+                TokenKind::Plain
+            }
+        }
+        else
+        {
+            i += 1;
+            TokenKind::Plain
+        };
+
+        push( kind, &chars[start..i] );
+    }
+
+    tokens
+}
+// Runs on to the end of the block comment, or the end of the line if it carries on past it
+fn continue_comment(chars: &[char], i: &mut usize, in_comment: &mut bool)
+{
+    while *i < chars.len()
+    {
+        if chars[*i] == '*' && chars.get( *i + 1 ) == Some( &'/' )
+        {
+            *in_comment = false;
+            *i += 2;
+            return;
+        }
+
+        *i += 1;
+    }
+}
+// The file named by an #include line, as written
+pub fn include_path(line: &str) -> Option<&str>
+{
+    let rest = line.trim_start().strip_prefix( "#include" )?.trim();
+    let rest = rest.strip_prefix( '"' )?;
+
+    rest.find( '"' ).map( |end| &rest[..end] )
+}
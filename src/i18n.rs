@@ -0,0 +1,472 @@
+/*
+	Sven Co-op Plugin Manager Version 2.0
+
+Copyright (C) 2025 Outerbeast
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program. If not, see <https://www.gnu.org/licenses/>.
+*/
+// Translations of the messages shown to the user. The .po files under lang/ are also bundled into the UI by build.rs,
+// so a message written with tr! in Rust or @tr in Slint is translated from the same place.
+use std::
+{
+    collections::HashMap,
+    fmt::{ Display, Write },
+    sync::RwLock
+};
+
+// Translates a message and fills in its {} placeholders, written the same way as Slint's @tr
+#[macro_export]
+macro_rules! tr
+{
+    ( $message:literal $(, $arg:expr )* $(,)? ) =>
+    {
+        $crate::i18n::format( &$crate::i18n::translate( $message ), &[$( &$arg as &dyn std::fmt::Display ),*] )
+    };
+}
+// Same as tr! for a message that depends on a count, picking the catalogue's form for that count.
+// The count is the only argument unless others are given after it.
+#[macro_export]
+macro_rules! trn
+{
+    ( $singular:literal, $plural:literal, $count:expr, $( $arg:expr ),+ $(,)? ) =>
+    {
+        $crate::i18n::format( &$crate::i18n::translate_plural( $singular, $plural, $count as u64 ), &[$( &$arg as &dyn std::fmt::Display ),+] )
+    };
+
+    ( $singular:literal, $plural:literal, $count:expr $(,)? ) =>
+    {
+        $crate::i18n::format( &$crate::i18n::translate_plural( $singular, $plural, $count as u64 ), &[&$count as &dyn std::fmt::Display] )
+    };
+}
+
+pub struct Language
+{
+    pub code: &'static str,// name of its folder in lang/
+    pub name: &'static str,// written in the language itself, for picking it in the settings
+    catalogue: &'static str
+}
+
+macro_rules! bundled
+{
+    ( $code:literal, $name:literal ) =>
+    {
+        Language
+        {
+            code: $code,
+            name: $name,
+            catalogue: include_str!( concat!( "../lang/", $code, "/LC_MESSAGES/", env!( "CARGO_PKG_NAME" ), ".po" ) )
+        }
+    };
+}
+// English is what the messages are written in, so it has no catalogue
+pub const LANGUAGES: &[Language] =
+&[
+    bundled!( "es", "Español" ),
+    bundled!( "ru", "Русский" )
+];
+
+#[derive( Default )]
+struct Catalogue
+{
+    code: &'static str,
+    plural_rule: String,// the plural= expression from the Plural-Forms header
+    messages: HashMap<String, Vec<String>>// one translation, or one for each plural form
+}
+
+static ACTIVE: RwLock<Option<Catalogue>> = RwLock::new( None );
+
+// The bundled language for a locale such as "es-ES" or "ru_RU.UTF-8", matching only the language part if the region isn't bundled
+pub fn find_language(locale: &str) -> Option<&'static Language>
+{
+    let locale = locale.split( ['.', '@'] ).next().unwrap_or_default().replace( '-', "_" );
+    let base = locale.split( '_' ).next().unwrap_or_default();
+
+    LANGUAGES.iter().find( |l| l.code.eq_ignore_ascii_case( &locale ) )
+    .or_else( || LANGUAGES.iter().find( |l| l.code.split( '_' ).next().is_some_and( |c| c.eq_ignore_ascii_case( base ) ) ) )
+}
+// Sets the language messages are shown in, from the language in the settings or the system's if that is empty.
+// Anything without a bundled translation is shown in English.
+pub fn set_language(setting: &str)
+{
+    let language =
+    match setting.trim()
+    {
+        "" => sys_locale::get_locale().and_then( |locale| find_language( &locale ) ),
+        code => find_language( code )
+    };
+
+    let catalogue = language.map( |l| Catalogue { code: l.code, ..parse_po( l.catalogue ) } ).unwrap_or_default();
+
+    if let Ok( mut active ) = ACTIVE.write()
+    {
+        *active = Some( catalogue );
+    }
+}
+// Code of the language in use, empty for English
+pub fn current_language() -> &'static str
+{
+    ACTIVE.read().ok().and_then( |a| a.as_ref().map( |c| c.code ) ).unwrap_or_default()
+}
+// Switches the UI's @tr strings to the language in use. Only works once a window has been created.
+pub fn apply_to_ui()
+{
+    if let Err( e ) = slint::select_bundled_translation( current_language() )
+    {
+        eprintln!( "Failed to select the UI language: {}", e );
+    }
+}
+
+pub fn translate(message: &str) -> String
+{
+    ACTIVE.read().ok()
+        .and_then( |a| a.as_ref().and_then( |c| c.messages.get( message ).and_then( |m| m.first().cloned() ) ) )
+    .unwrap_or_else( || message.to_string() )
+}
+// Looked up by the singular, as gettext does. English has the one form for 1 and the other for anything else.
+pub fn translate_plural(singular: &str, plural: &str, count: u64) -> String
+{
+    let translated = ACTIVE.read().ok().and_then( |a| a.as_ref().and_then( |c|
+    {
+        let forms = c.messages.get( singular ).filter( |m| m.len() > 1 )?;
+        forms.get( plural_form( &c.plural_rule, count ) ).cloned()
+    }));
+
+    translated.unwrap_or_else( || match count { 1 => singular, _ => plural }.to_string() )
+}
+// Works out which plural form a count takes from a Plural-Forms rule such as "(n != 1)".
+// Rules are C expressions using n, numbers, comparisons, && || ! and ?:
+fn plural_form(rule: &str, n: u64) -> usize
+{
+    match rule.trim()
+    {
+        "" => usize::from( n != 1 ),
+        rule => PluralRule { rest: rule, n }.conditional() as usize
+    }
+}
+
+// Operators of a plural rule from the loosest binding to the tightest, longer ones first so "<=" isn't read as "<"
+const PLURAL_OPERATORS: &[&[&str]] = &[&["||"], &["&&"], &["==", "!="], &["<=", ">=", "<", ">"], &["+", "-"], &["*", "/", "%"]];
+
+struct PluralRule<'a>
+{
+    rest: &'a str,
+    n: u64
+}
+
+impl PluralRule<'_>
+{
+    fn eat(&mut self, token: &str) -> bool
+    {
+        self.rest = self.rest.trim_start();
+
+        match self.rest.strip_prefix( token )
+        {
+            Some( rest ) =>
+            {
+                self.rest = rest;
+                true
+            }
+
+            None => false
+        }
+    }
+
+    fn conditional(&mut self) -> u64
+    {
+        let condition = self.binary( 0 );
+
+        if !self.eat( "?" )
+        {
+            return condition;
+        }
+
+        let ( yes, _, no ) = ( self.conditional(), self.eat( ":" ), self.conditional() );
+
+        match condition
+        {
+            0 => no,
+            _ => yes
+        }
+    }
+
+    fn binary(&mut self, level: usize) -> u64
+    {
+        let Some( operators ) = PLURAL_OPERATORS.get( level ) else { return self.operand() };
+        let mut value = self.binary( level + 1 );
+
+        while let Some( op ) = operators.iter().find( |op| self.eat( op ) )
+        {
+            let rhs = self.binary( level + 1 );
+
+            value =
+            match *op
+            {
+                "||" => u64::from( value != 0 || rhs != 0 ),
+                "&&" => u64::from( value != 0 && rhs != 0 ),
+                "==" => u64::from( value == rhs ),
+                "!=" => u64::from( value != rhs ),
+                "<=" => u64::from( value <= rhs ),
+                ">=" => u64::from( value >= rhs ),
+                "<" => u64::from( value < rhs ),
+                ">" => u64::from( value > rhs ),
+                "+" => value.wrapping_add( rhs ),
+                "-" => value.wrapping_sub( rhs ),
+                "*" => value.wrapping_mul( rhs ),
+                "/" => value.checked_div( rhs ).unwrap_or_default(),
+                _ => value.checked_rem( rhs ).unwrap_or_default()
+            };
+        }
+
+        value
+    }
+
+    fn operand(&mut self) -> u64
+    {
+        if self.eat( "!" )
+        {
+            return u64::from( self.operand() == 0 );
+        }
+
+        if self.eat( "(" )
+        {
+            let value = self.conditional();
+            self.eat( ")" );
+
+            return value;
+        }
+
+        if self.eat( "n" )
+        {
+            return self.n;
+        }
+
+        let digits = self.rest.find( |c: char| !c.is_ascii_digit() ).unwrap_or( self.rest.len() );
+        let ( number, rest ) = self.rest.split_at( digits );
+        self.rest = rest;
+
+        number.parse().unwrap_or_default()
+    }
+}
+// Fills in {} with the next argument and {0}, {1}... with the one numbered. {{ and }} are literal braces.
+pub fn format(template: &str, args: &[&dyn Display]) -> String
+{
+    let mut out = String::with_capacity( template.len() );
+    let mut chars = template.chars().peekable();
+    let mut next_arg = 0;
+
+    while let Some( c ) = chars.next()
+    {
+        match c
+        {
+            '{' if chars.peek() == Some( &'{' ) =>
+            {
+                chars.next();
+                out.push( '{' );
+            }
+
+            '}' if chars.peek() == Some( &'}' ) =>
+            {
+                chars.next();
+                out.push( '}' );
+            }
+
+            '{' =>
+            {
+                let mut index = String::new();
+
+                while let Some( d ) = chars.next_if( |d| *d != '}' )
+                {
+                    index.push( d );
+                }
+
+                chars.next();
+
+                let arg =
+                match index.parse::<usize>()
+                {
+                    Ok( i ) => args.get( i ),
+                    Err( _ ) =>
+                    {
+                        next_arg += 1;
+                        args.get( next_arg - 1 )
+                    }
+                };
+
+                if let Some( arg ) = arg
+                {
+                    let _ = write!( out, "{}", arg );
+                }
+            }
+
+            _ => out.push( c )
+        }
+    }
+
+    out
+}
+// Reads the translations out of a .po file. Messages with a msgctxt are keyed "context\u{4}msgid" as gettext does,
+// plural ones by their singular msgid. Untranslated entries are left out, so they fall back to English.
+fn parse_po(po: &str) -> Catalogue
+{
+    #[derive( Default )]
+    struct Entry
+    {
+        context: Option<String>,
+        msgid: String,
+        msgstr: Vec<String>,// msgstr, or msgstr[0], msgstr[1]...
+        fuzzy: bool
+    }
+
+    let mut catalogue = Catalogue::default();
+    let mut entry = Entry::default();
+    let mut reading: Option<fn(&mut Entry) -> &mut String> = None;
+
+    let mut finish = |entry: &mut Entry|
+    {
+        let Entry { context, msgid, msgstr, fuzzy } = std::mem::take( entry );
+
+        if msgid.is_empty() && context.is_none()
+        {   // The header, the only part of it needed is the plural rule
+            catalogue.plural_rule = msgstr.concat().lines()
+                .find_map( |l| l.strip_prefix( "Plural-Forms:" ) )
+                .and_then( |forms| forms.split( ';' ).find_map( |f| f.trim().strip_prefix( "plural=" ) ) )
+                .unwrap_or_default()
+            .to_string();
+        }
+        else if !msgstr.is_empty() && msgstr.iter().all( |s| !s.is_empty() ) && !fuzzy
+        {
+            let key = match context { Some( context ) => format!( "{}\u{4}{}", context, msgid ), None => msgid };
+            catalogue.messages.insert( key, msgstr );
+        }
+    };
+
+    for line in po.lines().map( str::trim )
+    {   // Entries are separated by blank lines, with their comments before the msgid
+        if line.is_empty()
+        {
+            finish( &mut entry );
+            reading = None;
+        }
+        else if let Some( rest ) = line.strip_prefix( "msgctxt " )
+        {
+            entry.context = Some( unquote( rest ) );
+            reading = Some( |e| e.context.get_or_insert_default() );
+        }
+        else if let Some( rest ) = line.strip_prefix( "msgid " )
+        {
+            entry.msgid = unquote( rest );
+            reading = Some( |e| &mut e.msgid );
+        }
+        else if let Some( rest ) = line.strip_prefix( "msgstr" )
+        {   // "msgstr " or "msgstr[n] ", the forms come in order
+            let text = rest.trim_start_matches( |c: char| c == '[' || c == ']' || c.is_ascii_digit() );
+            entry.msgstr.push( unquote( text ) );
+            reading = Some( |e| e.msgstr.last_mut().expect( "a msgstr was just read" ) );
+        }
+        else if line.starts_with( '"' )
+        {
+            if let Some( field ) = reading
+            {
+                field( &mut entry ).push_str( &unquote( line ) );
+            }
+        }
+        else
+        {   // msgid_plural and comments end the string being read, the English plural is in the source already
+            reading = None;
+            entry.fuzzy |= line.starts_with( "#," ) && line.contains( "fuzzy" );
+        }
+    }
+
+    finish( &mut entry );
+    catalogue
+}
+
+fn unquote(text: &str) -> String
+{
+    let text = text.trim().trim_start_matches( '"' ).strip_suffix( '"' ).unwrap_or_default();
+    let mut out = String::with_capacity( text.len() );
+    let mut chars = text.chars();
+
+    while let Some( c ) = chars.next()
+    {
+        match c
+        {
+            '\\' =>
+            match chars.next()
+            {
+                Some( 'n' ) => out.push( '\n' ),
+                Some( 't' ) => out.push( '\t' ),
+                Some( other ) => out.push( other ),
+                None => {}
+            },
+
+            _ => out.push( c )
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    const RUSSIAN_RULE: &str = "(n%10==1 && n%100!=11 ? 0 : n%10>=2 && n%10<=4 && (n%100<10 || n%100>=20) ? 1 : 2)";
+
+    #[test]
+    fn plural_form_follows_the_rule()
+    {
+        let forms = [1, 2, 4, 5, 11, 12, 21, 22, 25, 111].map( |n| plural_form( RUSSIAN_RULE, n ) );
+
+        assert_eq!( forms, [0, 1, 1, 2, 2, 2, 0, 1, 2, 2] );
+        assert_eq!( [0, 1, 2].map( |n| plural_form( "(n != 1)", n ) ), [1, 0, 1] );
+        assert_eq!( [0, 1, 2].map( |n| plural_form( "", n ) ), [1, 0, 1] );
+    }
+
+    #[test]
+    fn parse_po_reads_plurals_and_contexts()
+    {
+        let po = concat!(
+            "msgid \"\"\nmsgstr \"\"\n\"Language: ru\\n\"\n\"Plural-Forms: nplurals=3; plural=(n%10==1 ? 0 : 1);\\n\"\n\n",
+            "msgctxt \"noun\"\nmsgid \"Install\"\nmsgstr \"Установка\"\n\n",
+            "msgctxt \"verb\"\nmsgid \"Install\"\nmsgstr \"Установить\"\n\n",
+            "msgid \"{} plugin\"\nmsgid_plural \"{} plugins\"\nmsgstr[0] \"{} плагин\"\nmsgstr[1] \"{} плагина\"\nmsgstr[2] \"{} плагинов\"\n\n",
+            "msgid \"Half done\"\nmsgid_plural \"Half done\"\nmsgstr[0] \"Готово\"\nmsgstr[1] \"\"\n\n",
+            "#, fuzzy\nmsgid \"Name\"\nmsgstr \"Имя\"\n" );
+
+        let catalogue = parse_po( po );
+
+        assert_eq!( catalogue.plural_rule, "(n%10==1 ? 0 : 1)" );
+        assert_eq!( catalogue.messages.get( "noun\u{4}Install" ), Some( &vec!["Установка".to_string()] ) );
+        assert_eq!( catalogue.messages.get( "verb\u{4}Install" ), Some( &vec!["Установить".to_string()] ) );
+        assert_eq!( catalogue.messages.get( "{} plugin" ).map( Vec::len ), Some( 3 ) );
+        assert!( !catalogue.messages.contains_key( "Install" ) );
+        assert!( !catalogue.messages.contains_key( "Half done" ) );
+        assert!( !catalogue.messages.contains_key( "Name" ) );
+    }
+
+    #[test]
+    fn bundled_catalogues_have_every_plural_form()
+    {
+        for language in LANGUAGES
+        {
+            let catalogue = parse_po( language.catalogue );
+            let forms = catalogue.messages.get( "{} plugin" ).map( Vec::len ).unwrap_or_default();
+            let most = ( 0..200 ).map( |n| plural_form( &catalogue.plural_rule, n ) ).max().unwrap_or_default();
+
+            assert!( !catalogue.plural_rule.is_empty(), "{} has no plural rule", language.code );
+            assert_eq!( forms, most + 1, "{} has {} forms for its rule", language.code, forms );
+        }
+    }
+}
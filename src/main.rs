@@ -20,9 +20,18 @@ along with this program. If not, see <https://www.gnu.org/licenses/>.
 slint::include_modules!();
 
 pub mod driver;
+pub mod cli;
 pub mod config;
 pub mod plugin;
+pub mod health;
+pub mod compile_log;
+pub mod highlight;
+pub mod i18n;
 pub mod utils;
+pub mod steam;
+pub mod maps;
+pub mod rcon;
+pub mod catalogue;
 pub mod app;
 
 pub const APPNAME: &str = env!( "CARGO_PKG_NAME" );
@@ -0,0 +1,80 @@
+/*
+	Sven Co-op Plugin Manager Version 2.0
+
+Copyright (C) 2025 Outerbeast
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program. If not, see <https://www.gnu.org/licenses/>.
+*/
+use std::
+{
+    fs,
+    collections::{ BTreeSet, HashSet },
+    path::Path
+};
+
+pub const FILENAME_MAPCYCLE: &str = "mapcycle.txt";
+pub const MAP_EXT: &str = "bsp";
+// Content folders maps are loaded from, next to each other in the game's root folder
+pub const MAP_CONTENT_DIRS: [&str; 3] = ["svencoop", "svencoop_addon", "svencoop_downloads"];
+
+// Names of every map installed in the game, without the .bsp extension, sorted and without duplicates
+pub fn find_maps(svencoop_dir: &Path) -> Vec<String>
+{
+    let root = svencoop_dir.parent().unwrap_or( svencoop_dir );
+    let mut maps = BTreeSet::new();
+
+    for dir in MAP_CONTENT_DIRS
+    {
+        let Ok( entries ) = fs::read_dir( root.join( dir ).join( "maps" ) ) else { continue };
+
+        for path in entries.filter_map( Result::ok ).map( |e| e.path() )
+        {
+            if path.extension().is_some_and( |e| e.eq_ignore_ascii_case( MAP_EXT ) )
+            && let Some( stem ) = path.file_stem().and_then( |s| s.to_str() )
+            {
+                maps.insert( stem.to_ascii_lowercase() );
+            }
+        }
+    }
+
+    maps.into_iter().collect()
+}
+// Maps in the server's map cycle, one per line. Comments and blank lines are skipped.
+pub fn read_mapcycle(svencoop_dir: &Path) -> HashSet<String>
+{
+    fs::read_to_string( svencoop_dir.join( FILENAME_MAPCYCLE ) )
+        .unwrap_or_default()
+        .lines()
+        .map( |l| l.split( "//" ).next().unwrap_or( "" ).trim() )
+        .filter_map( |l| l.split_whitespace().next() )
+        .map( str::to_ascii_lowercase )
+    .collect()
+}
+// Maps of the same campaign usually share a prefix, "hl_c01_a1" and "hl_c02_a1" are both "hl"
+pub fn series_prefix(map: &str) -> &str
+{
+    match map.find( '_' )
+    {
+        Some( i ) if i > 0 => &map[..i],
+        _ =>
+        {   // No separator, as in "of1a1": use the leading letters
+            let end = map.find( |c: char| !c.is_ascii_alphabetic() ).unwrap_or( map.len() );
+
+            match end
+            {
+                0 => map,
+                _ => &map[..end]
+            }
+        }
+    }
+}
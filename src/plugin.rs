@@ -19,12 +19,20 @@ use std::
 {
     fs,
     io,
-    collections::HashMap,
+    collections::{ HashMap, HashSet },
+    hash::{ DefaultHasher, Hash, Hasher },
     ops::Not,
-    path::{ Path, PathBuf }
+    path::{ Component, Path, PathBuf },
+    time::SystemTime
 };
 
-use crate::config::SVENCOOP_PATH;
+use crate::
+{
+    config::SVENCOOP_PATH,
+    health::HealthMonitor,
+    tr,
+    utils
+};
 
 pub const FILENAME_PLUGINS: &str = "default_plugins.txt";
 pub const FILENAME_DISABLED_PLUGINS: &str = "disabled_plugins.txt";
@@ -33,7 +41,19 @@ pub const SCRIPT_EXT: &str = "as";
 pub const CHECKED: &str = "✔";
 pub const UNCHECKED: &str = "☐";
 
-#[derive( Debug, Default, Clone, Copy )]
+// Replace "svencoop" with "svencoop_addon" in the base path - "Sven Co-op/svencoop_addon"
+pub fn addon_dir(svencoop_dir: &Path) -> PathBuf
+{
+    let parent = svencoop_dir.parent().unwrap_or( svencoop_dir );
+    parent.join( "svencoop_addon" )
+}
+// "Sven Co-op/svencoop_addon/scripts/plugins"
+pub fn addon_plugins_dir(svencoop_dir: &Path) -> PathBuf
+{
+    addon_dir( svencoop_dir ).join( PLUGINS_DIR )
+}
+
+#[derive( Debug, Default, Clone, Copy, PartialEq )]
 #[repr(isize)]
 pub enum AdminLevel// straight from AdminLevel_t: https://sven-coop.github.io/AdminLevel_t
 {
@@ -113,11 +133,88 @@ impl Not for PluginState
     }
 }
 
+// Fingerprint of a plugin file, to tell when something else has written to it
+#[derive( Debug, Clone, PartialEq )]
+struct FileStamp
+{
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    hash: Option<u64>
+}
+
+impl FileStamp
+{
+    fn capture(path: &Path) -> Self
+    {
+        let modified = fs::metadata( path ).and_then( |m| m.modified() ).ok();
+        let hash = fs::read( path ).ok().map( |bytes|
+        {
+            let mut hasher = DefaultHasher::new();
+            bytes.hash( &mut hasher );
+
+            hasher.finish()
+        });
+
+        Self { path: path.to_path_buf(), modified, hash }
+    }
+}
+
+#[derive( Debug, Default, Clone, PartialEq )]
+pub struct DiskSnapshot
+{
+    files: Vec<FileStamp>
+}
+
+impl DiskSnapshot
+{
+    pub fn capture(svencoop_dir: &Path) -> Self
+    {
+        Self
+        {
+            files: [FILENAME_PLUGINS, FILENAME_DISABLED_PLUGINS]
+                .iter()
+                .map( |f| FileStamp::capture( &svencoop_dir.join( f ) ) )
+            .collect()
+        }
+    }
+    // Cheap check first - the files are only read again if their modified time moved.
+    // A file touched without its contents changing takes the new time, so it isn't read again every check.
+    pub fn has_changed(&mut self) -> bool
+    {
+        let mut changed = false;
+
+        for stamp in &mut self.files
+        {
+            let modified = fs::metadata( &stamp.path ).and_then( |m| m.modified() ).ok();
+
+            if modified == stamp.modified
+            {
+                continue;
+            }
+
+            let current = FileStamp::capture( &stamp.path );
+
+            match current.hash == stamp.hash
+            {
+                true => stamp.modified = current.modified,
+                false => changed = true
+            }
+        }
+
+        changed
+    }
+}
+
 #[derive( Default )]
 pub struct PluginContext
 {
     pub plugins: HashMap<String, PluginEntry>,
-    pub selected_plugin_name: Option<String>
+    pub selected_plugin_name: Option<String>,// the plugin shown in the detail panel
+    pub selected: HashSet<String>,// every plugin selected in the list, including the one above
+    pub selection_anchor: Option<String>,// where a shift-click range starts from
+    pub baseline: HashMap<String, PluginEntry>,// plugins as they were on disk at load or last save
+    pub disk: DiskSnapshot,
+    pub health: HealthMonitor// script status for the plugin list, checked in the background
 }
 
 impl PluginContext
@@ -126,8 +223,63 @@ impl PluginContext
     {
         Self
         {
+            baseline: plugins.clone(),
             plugins,
             selected_plugin_name: None,
+            selected: HashSet::new(),
+            selection_anchor: None,
+            disk: DiskSnapshot::default(),
+            health: HealthMonitor::default()
+        }
+    }
+
+    // Moves a plugin to a new key when it is renamed, the selection follows it
+    pub fn rename(&mut self, key: &str, new_key: &str)
+    {
+        let Some( plugin ) = self.plugins.remove( key ) else { return };
+        self.plugins.insert( new_key.to_string(), plugin );
+
+        if self.selected.remove( key )
+        {
+            self.selected.insert( new_key.to_string() );
+        }
+
+        for k in [&mut self.selected_plugin_name, &mut self.selection_anchor].into_iter().flatten()
+        {
+            if k == key
+            {
+                *k = new_key.to_string();
+            }
+        }
+    }
+
+    pub fn select_only(&mut self, key: &str)
+    {
+        self.selected = HashSet::from( [key.to_string()] );
+        self.selected_plugin_name = Some( key.to_string() );
+        self.selection_anchor = Some( key.to_string() );
+    }
+
+    pub fn clear_selection(&mut self)
+    {
+        self.selected.clear();
+        self.selected_plugin_name = None;
+        self.selection_anchor = None;
+    }
+    // Forgets selected plugins that are gone, e.g. after a removal or a reload
+    pub fn prune_selection(&mut self)
+    {
+        let plugins = &self.plugins;
+        self.selected.retain( |k| plugins.contains_key( k ) );
+
+        if self.selected_plugin_name.as_ref().is_some_and( |k| !plugins.contains_key( k ) )
+        {
+            self.selected_plugin_name = self.selected.iter().next().cloned();
+        }
+
+        if self.selection_anchor.as_ref().is_some_and( |k| !plugins.contains_key( k ) )
+        {
+            self.selection_anchor = self.selected_plugin_name.clone();
         }
     }
 
@@ -135,6 +287,131 @@ impl PluginContext
     {
         self.plugins.contains_key( name )
     }
+    // The entry differs from what is on disk, or isn't on disk at all yet
+    pub fn is_modified(&self, key: &str) -> bool
+    {
+        self.plugins.get( key ).is_some_and( |p| self.baseline.get( key ).is_none_or( |b| !b.same_settings( p ) ) )
+    }
+
+    pub fn has_unsaved_changes(&self) -> bool
+    {
+        self.plugins.len() != self.baseline.len()
+        || self.plugins.iter().any( |(k, p)| self.baseline.get( k ).is_none_or( |b| !b.same_settings( p ) ) )
+    }
+    // Call after the plugin files have been written, what we hold now matches the disk
+    pub fn mark_saved(&mut self, svencoop_dir: &Path)
+    {
+        self.baseline = self.plugins.clone();
+        self.disk = DiskSnapshot::capture( svencoop_dir );
+    }
+}
+
+// The two map lists a plugin can have
+#[derive( Debug, Clone, Copy, PartialEq )]
+pub enum MapFilter
+{
+    Included,
+    Excluded
+}
+// Map lists are written separated by semicolons, but commas and spaces are read too
+pub fn split_maps(list: &str) -> Vec<&str>
+{
+    list.split( |c: char| c == ';' || c == ',' || c.is_whitespace() )
+        .filter( |m| !m.is_empty() )
+    .collect()
+}
+// Finds the file a plugin's script path points to. The game looks in
+// "Sven Co-op/svencoop_addon/scripts/plugins" before "Sven Co-op/svencoop/scripts/plugins".
+pub fn resolve_script_path(svencoop_dir: &Path, script: &str) -> Option<PathBuf>
+{
+    [addon_plugins_dir( svencoop_dir ), svencoop_dir.join( PLUGINS_DIR )]
+        .into_iter()
+        .map( |dir| dir.join( script ).with_extension( SCRIPT_EXT ) )
+    .find( |path| path.is_file() )
+}
+// Works out which file an #include points to. Paths are relative to the including file,
+// falling back to the plugin folders for includes written from there.
+pub fn resolve_include(svencoop_dir: &Path, including: &Path, include: &str) -> Option<PathBuf>
+{
+    let relative = Path::new( include );
+    let relative =
+    match relative.extension()
+    {
+        Some( _ ) => relative.to_path_buf(),
+        None => relative.with_extension( SCRIPT_EXT )
+    };
+
+    let beside = including.parent().map( |dir| dir.join( &relative ) );
+
+    match beside
+    {
+        Some( path ) if path.is_file() => Some( path ),
+        _ => resolve_script_path( svencoop_dir, &relative.with_extension( "" ).to_string_lossy() )
+    }
+}
+// Checks for the values typed into the detail fields, each gives back what is wrong with the value if anything.
+// Quotes would end the value early in the plugin file, so none of them can have one.
+pub fn check_plugin_name(name: &str) -> Option<String>
+{
+    match name.trim()
+    {
+        "" => Some( tr!( "The name can't be empty" ) ),
+        n if n.contains( '"' ) => Some( tr!( "The name can't contain quotes" ) ),
+        _ => None
+    }
+}
+
+pub fn check_script_path(script: &str) -> Option<String>
+{
+    let script = script.trim();
+
+    if script.is_empty()
+    {
+        Some( tr!( "A script is required" ) )
+    }
+    else if script.contains( '"' )
+    {
+        Some( tr!( "The script path can't contain quotes" ) )
+    }
+    else if Path::new( script ).is_absolute() || script.contains( ':' ) || Path::new( script ).components().any( |c| c == Component::ParentDir )
+    {
+        Some( tr!( "The script has to be inside {}", PLUGINS_DIR ) )
+    }
+    else if script.to_ascii_lowercase().ends_with( &format!( ".{}", SCRIPT_EXT ) )
+    {
+        Some( tr!( "Leave out the .{} extension", SCRIPT_EXT ) )
+    }
+    else
+    {
+        None
+    }
+}
+
+pub fn check_concommandns(ns: &str) -> Option<String>
+{
+    match ns.trim().chars().all( |c| c.is_ascii_alphanumeric() || c == '_' )
+    {
+        true => None,
+        false => Some( tr!( "Only letters, numbers and _ can be used" ) )
+    }
+}
+
+pub fn check_map_list(list: &str) -> Option<String>
+{
+    if list.contains( '"' )
+    {
+        return Some( tr!( "Map names can't contain quotes" ) );
+    }
+
+    split_maps( list )
+        .into_iter()
+        .find( |m| m.contains( ['/', '\\'] ) || m.to_ascii_lowercase().ends_with( ".bsp" ) )
+    .map( |m| tr!( "'{}' should be just the map name, without a folder or .bsp", m ) )
+}
+// Whichever separator a map list already uses, so edits don't change its style
+fn map_separator(list: &str) -> char
+{
+    list.chars().find( |c| *c == ';' || *c == ',' || *c == ' ' ).unwrap_or( ';' )
 }
 
 #[derive(Debug, Clone)]
@@ -173,41 +450,97 @@ impl PluginEntry
     {
         self.state = self.state.toggle();
     }
-
-    pub fn validate_plugin_install(&self) -> bool
+    pub fn map_list(&self, filter: MapFilter) -> &str
     {
-        Self::validate_script_install( &self.script.clone() )
+        match filter
+        {
+            MapFilter::Included => &self.maps_included,
+            MapFilter::Excluded => &self.maps_excluded
+        }
     }
 
-    fn validate_script_install(script: &str) -> bool
+    fn map_list_mut(&mut self, filter: MapFilter) -> &mut String
     {
-        let svencoop_path = SVENCOOP_PATH.get().expect( "SVENCOOP_PATH missing" );
-        // Base folder - "Sven Co-op/svencoop/scripts/plugins"
-        let base_path = svencoop_path
-            .join( PLUGINS_DIR )
-            .join( script )
-            .with_extension( SCRIPT_EXT );
-        // Check main plugins folder first
-        if base_path.is_file()
+        match filter
         {
-            #[cfg(debug_assertions)] println!( "{:?} - Valid?: true ", base_path );
-            return true;
+            MapFilter::Included => &mut self.maps_included,
+            MapFilter::Excluded => &mut self.maps_excluded
         }
-        // Check addons folder - "Sven Co-op/svencoop_addon/scripts/plugins"
-        if let Some( parent ) = svencoop_path.parent()
+    }
+    // Adds a map to one of the lists, keeping whichever separator the list already uses. Returns false if it was already there.
+    pub fn add_map(&mut self, filter: MapFilter, map: &str) -> bool
+    {
+        let map = map.trim();
+        let list = self.map_list_mut( filter );
+
+        if map.is_empty() || split_maps( list ).iter().any( |m| m.eq_ignore_ascii_case( map ) )
         {
-            let addon_path = parent
-                .join( "svencoop_addon" )
-                .join( PLUGINS_DIR )
-                .join( script )
-                .with_extension( SCRIPT_EXT );
+            return false;
+        }
 
-            #[cfg(debug_assertions)] println!( "{:?} - Valid?: {}", addon_path, addon_path.is_file() );
+        let separator = map_separator( list );
 
-            return addon_path.is_file();
+        if !list.trim().is_empty()
+        {
+            list.push( separator );
         }
 
-        false
+        list.push_str( map );
+
+        true
+    }
+    // Replaces one of the lists, e.g. with what was picked in the map picker
+    pub fn set_map_list(&mut self, filter: MapFilter, maps: &[String])
+    {
+        let list = self.map_list_mut( filter );
+        *list = maps.join( &map_separator( list ).to_string() );
+    }
+    // Takes a map out of one of the lists. Returns false if it wasn't in it.
+    pub fn remove_map(&mut self, filter: MapFilter, map: &str) -> bool
+    {
+        let map = map.trim();
+        let list = self.map_list_mut( filter );
+        let maps = split_maps( list );
+
+        if !maps.iter().any( |m| m.eq_ignore_ascii_case( map ) )
+        {
+            return false;
+        }
+
+        let separator = map_separator( list );
+        *list = maps
+            .into_iter()
+            .filter( |m| !m.eq_ignore_ascii_case( map ) )
+            .collect::<Vec<_>>()
+        .join( &separator.to_string() );
+
+        true
+    }
+    // Compares what gets written to the plugin file, ignoring where the entry was read from
+    pub fn same_settings(&self, other: &PluginEntry) -> bool
+    {
+        self.name == other.name
+        && self.script == other.script
+        && self.state == other.state
+        && self.concommandns == other.concommandns
+        && self.adminlevel == other.adminlevel
+        && self.maps_included == other.maps_included
+        && self.maps_excluded == other.maps_excluded
+    }
+
+    pub fn validate_plugin_install(&self) -> bool
+    {
+        Self::validate_script_install( &self.script.clone() )
+    }
+
+    fn validate_script_install(script: &str) -> bool
+    {
+        let Some( svencoop_path ) = SVENCOOP_PATH.get() else { return false };
+        let resolved = resolve_script_path( &svencoop_path, script );
+
+        #[cfg(debug_assertions)] println!( "{} - Valid?: {:?}", script, resolved );
+
+        resolved.is_some()
     }
     // New plugin entry, name and script are required minimum fields, returns (key, Plugin) tuple
     // Maybe this should be a Plugin constructor instead?
@@ -243,21 +576,20 @@ impl PluginEntry
     pub fn install_plugin(script: &str, svencoop_dir: &Path) -> io::Result<()>
     {
         let src = PathBuf::from( script );
-        // Replace "svencoop" with "svencoop_addon" in the base path
-        let parent = svencoop_dir.parent().unwrap_or( svencoop_dir );
-        let addon_dir = parent.join( "svencoop_addon" );
         // Destination: svencoop_addon/scripts/plugins/<filename>
-        let dst = addon_dir
-            .join( "scripts" )
-            .join( "plugins" )
-            .join( src.file_name().unwrap_or_default() );
+        Self::install_addon_file( &src, &Path::new( PLUGINS_DIR ).join( src.file_name().unwrap_or_default() ), svencoop_dir )
+    }
+    // Copies a file to `relative` inside svencoop_addon, e.g. a sound a plugin comes with
+    pub fn install_addon_file(src: &Path, relative: &Path, svencoop_dir: &Path) -> io::Result<()>
+    {
+        let dst = addon_dir( svencoop_dir ).join( relative );
         // Ensure the destination directory exists
         if let Some( parent ) = dst.parent()
         {
             fs::create_dir_all( parent )?;
         }
         // Copy the file
-        fs::copy( &src, &dst )?;
+        fs::copy( src, &dst )?;
 
         Ok( () )
     }
@@ -397,6 +729,88 @@ pub fn load_plugins(text: &str, state: PluginState) -> HashMap<String, PluginEnt
 
     plugins
 }
+// Reads one plugin file, every entry in it is given the state passed in
+pub fn load_plugin_file(path: &Path, state: PluginState) -> Result<HashMap<String, PluginEntry>, io::Error>
+{
+    fs::read_to_string( path ).map( |text| load_plugins( &text, state ) )
+}
+// Puts the plugin files back as they were if a save was cut off between writing the two of them
+pub fn recover_plugin_files(svencoop_dir: &Path)
+{
+    match utils::recover_commit( &svencoop_dir.join( FILENAME_PLUGINS ) )
+    {
+        Ok( true ) => eprintln!( "A save of the plugin files didn't finish, the files were put back as they were" ),
+        Ok( false ) => {}
+        Err( e ) => eprintln!( "Failed to put back the plugin files after an unfinished save: {}", e )
+    }
+}
+// Reads both plugin files from the game folder, a missing disabled plugin file is not an error
+pub fn read_plugin_files(svencoop_dir: &Path) -> Result<HashMap<String, PluginEntry>, io::Error>
+{
+    recover_plugin_files( svencoop_dir );
+
+    let mut plugins = load_plugin_file( &svencoop_dir.join( FILENAME_PLUGINS ), PluginState::Enabled )?;
+
+    if let Ok( disabled ) = load_plugin_file( &svencoop_dir.join( FILENAME_DISABLED_PLUGINS ), PluginState::Disabled )
+    {
+        plugins.extend( disabled );
+    }
+
+    Ok( plugins )
+}
+// Three-way merge of plugin entries: changes made on either side since the base are kept.
+// When both sides changed the same entry differently ours wins, and the entry name is reported as a conflict.
+pub fn merge_plugins(base: &HashMap<String, PluginEntry>,
+    ours: &HashMap<String, PluginEntry>,
+    theirs: &HashMap<String, PluginEntry>) -> (HashMap<String, PluginEntry>, Vec<String>)
+{
+    // An entry removed here is the same as one that isn't there, so one deleted on both sides agrees
+    fn present(e: Option<&PluginEntry>) -> Option<&PluginEntry>
+    {
+        e.filter( |e| e.state != PluginState::Removed )
+    }
+
+    let same = |a: Option<&PluginEntry>, b: Option<&PluginEntry>|
+    match ( present( a ), present( b ) )
+    {
+        ( Some( a ), Some( b ) ) => a.same_settings( b ),
+        ( None, None ) => true,
+        _ => false
+    };
+
+    let keys: HashSet<&String> = base.keys().chain( ours.keys() ).chain( theirs.keys() ).collect();
+    let mut merged = HashMap::new();
+    let mut conflicts = Vec::new();
+
+    for key in keys
+    {
+        let ( b, o, t ) = ( base.get( key ), ours.get( key ), theirs.get( key ) );
+
+        let pick =
+        if same( o, t ) || same( b, t )
+        {
+            o
+        }
+        else if same( b, o )
+        {
+            t
+        }
+        else
+        {
+            conflicts.push( key.clone() );
+            o
+        };
+
+        if let Some( entry ) = pick
+        {
+            merged.insert( key.clone(), entry.clone() );
+        }
+    }
+
+    conflicts.sort_unstable_by_key( |k| k.to_ascii_lowercase() );
+
+    ( merged, conflicts )
+}
 // Returns missing plugins as a list, if any
 pub fn save_plugins(ctx: &PluginContext) -> Result<String, io::Error>
 {
@@ -424,8 +838,76 @@ pub fn save_plugins(ctx: &PluginContext) -> Result<String, io::Error>
         io::Error::other( "SVENCOOP_PATH not set" )
     })?;
 
-    fs::write( path.join( FILENAME_PLUGINS ), format!( "\"plugins\"\n{{{}}}", enabled_plugins ) )?;
-    fs::write( path.join( FILENAME_DISABLED_PLUGINS ),format!( "\"disabled_plugins\"\n{{{}}}", disabled_plugins ) )?;
+    let enabled_file = format!( "\"plugins\"\n{{{}}}", enabled_plugins );
+    let disabled_file = format!( "\"disabled_plugins\"\n{{{}}}", disabled_plugins );
+    // Both files go in together so a failed save can't leave them disagreeing with each other
+    utils::commit_files(
+    &[
+        ( &path.join( FILENAME_PLUGINS ), enabled_file.as_bytes() ),
+        ( &path.join( FILENAME_DISABLED_PLUGINS ), disabled_file.as_bytes() )
+    ])?;
 
     Ok( missing_plugins )
 }
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn plugins(entries: &[(&str, &str, PluginState)]) -> HashMap<String, PluginEntry>
+    {
+        entries.iter().map( |(name, script, state)|
+        {
+            let mut plugin = PluginEntry::new( name, script );
+            plugin.state = state.clone();
+
+            ( name.to_string(), plugin )
+        })
+        .collect()
+    }
+
+    #[test]
+    fn merge_keeps_changes_from_both_sides()
+    {
+        let base = plugins( &[( "A", "a", PluginState::Enabled ), ( "B", "b", PluginState::Enabled )] );
+        let ours = plugins( &[( "A", "a", PluginState::Disabled ), ( "B", "b", PluginState::Enabled )] );
+        let theirs = plugins( &[( "A", "a", PluginState::Enabled ), ( "B", "b2", PluginState::Enabled ), ( "C", "c", PluginState::Enabled )] );
+
+        let ( merged, conflicts ) = merge_plugins( &base, &ours, &theirs );
+
+        assert!( conflicts.is_empty() );
+        assert_eq!( merged["A"].state, PluginState::Disabled );
+        assert_eq!( merged["B"].script, "b2" );
+        assert!( merged.contains_key( "C" ) );
+    }
+
+    #[test]
+    fn merge_reports_both_sides_changing_an_entry_and_keeps_ours()
+    {
+        let base = plugins( &[( "A", "a", PluginState::Enabled )] );
+        let ours = plugins( &[( "A", "ours", PluginState::Enabled )] );
+        let theirs = plugins( &[( "A", "theirs", PluginState::Enabled )] );
+
+        let ( merged, conflicts ) = merge_plugins( &base, &ours, &theirs );
+
+        assert_eq!( conflicts, vec!["A".to_string()] );
+        assert_eq!( merged["A"].script, "ours" );
+    }
+
+    #[test]
+    fn merge_takes_removals_from_either_side()
+    {
+        let base = plugins( &[( "A", "a", PluginState::Enabled ), ( "B", "b", PluginState::Enabled )] );
+        let ours = plugins( &[( "A", "a", PluginState::Removed ), ( "B", "b", PluginState::Enabled )] );
+        let theirs = plugins( &[( "B", "b", PluginState::Enabled )] );
+        // Removed here and deleted on disk agree, deleted on disk and untouched here follows the disk
+        let ( merged, conflicts ) = merge_plugins( &base, &ours, &theirs );
+        assert!( conflicts.is_empty() );
+        assert!( merged.get( "A" ).is_none_or( |a| a.state == PluginState::Removed ) );
+
+        let ( merged, conflicts ) = merge_plugins( &base, &base, &theirs );
+        assert!( conflicts.is_empty() );
+        assert!( !merged.contains_key( "A" ) );
+    }
+}
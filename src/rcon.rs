@@ -0,0 +1,186 @@
+/*
+	Sven Co-op Plugin Manager Version 2.0
+
+Copyright (C) 2025 Outerbeast
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program. If not, see <https://www.gnu.org/licenses/>.
+*/
+// GoldSrc remote console over UDP, for reloading plugins on a running server once they are saved.
+// A command takes two round trips: one for a challenge number, then the command sent with it and the password.
+use std::
+{
+    io,
+    net::{ ToSocketAddrs, UdpSocket },
+    time::Duration
+};
+
+use crate::
+{
+    config::RconSettings,
+    tr
+};
+
+pub const RELOAD_COMMAND: &str = "as_reloadplugins";
+const HEADER: &[u8] = b"\xFF\xFF\xFF\xFF";
+const TIMEOUT: Duration = Duration::from_secs( 3 );
+// Long replies come in several packets, they are collected until none arrive for this long
+const REPLY_GAP: Duration = Duration::from_millis( 300 );
+
+fn packet(body: &str) -> Vec<u8>
+{
+    [HEADER, body.as_bytes(), b"\n"].concat()
+}
+// Text of a reply packet, without the header and the 'l' that marks printed output
+fn reply_text(packet: &[u8]) -> Option<String>
+{
+    let body = packet.strip_prefix( HEADER )?;
+    let body = body.strip_prefix( b"l" ).unwrap_or( body );
+
+    Some( String::from_utf8_lossy( body ).trim_end_matches( '\0' ).to_string() )
+}
+
+fn read_reply(socket: &UdpSocket) -> io::Result<String>
+{
+    let mut buffer = [0u8; 4096];
+
+    let len = socket.recv( &mut buffer ).map_err( |e|
+    match e.kind()
+    {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => io::Error::new( io::ErrorKind::TimedOut, tr!( "The server did not answer" ) ),
+        _ => e
+    })?;
+
+    reply_text( &buffer[..len] ).ok_or_else( || io::Error::new( io::ErrorKind::InvalidData, tr!( "The server's reply was not understood" ) ) )
+}
+// Runs a console command on the server and returns what it printed
+pub fn send_command(settings: &RconSettings, command: &str) -> io::Result<String>
+{
+    if settings.password.contains( '"' )
+    {
+        return Err( io::Error::new( io::ErrorKind::InvalidInput, tr!( "The RCON password can't contain quotes" ) ) );
+    }
+
+    let address = ( settings.host.trim(), settings.port ).to_socket_addrs()?
+        .next()
+    .ok_or_else( || io::Error::new( io::ErrorKind::NotFound, tr!( "'{}' could not be found", settings.host ) ) )?;
+
+    let socket = UdpSocket::bind( if address.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" } )?;
+    socket.connect( address )?;
+    socket.set_read_timeout( Some( TIMEOUT ) )?;
+
+    socket.send( &packet( "challenge rcon" ) )?;
+    let reply = read_reply( &socket )?;
+
+    let challenge = reply.trim()
+        .strip_prefix( "challenge rcon" )
+        .map( str::trim )
+        .filter( |c| !c.is_empty() && c.bytes().all( |b| b.is_ascii_digit() ) )
+    .ok_or_else( || io::Error::new( io::ErrorKind::InvalidData, tr!( "The server's reply was not understood" ) ) )?
+    .to_string();
+
+    socket.send( &packet( &format!( "rcon {} \"{}\" {}", challenge, settings.password, command ) ) )?;
+    let mut output = read_reply( &socket )?;
+
+    socket.set_read_timeout( Some( REPLY_GAP ) )?;
+
+    while let Ok( more ) = read_reply( &socket )
+    {
+        output.push_str( &more );
+    }
+
+    match output.trim()
+    {
+        o if o.starts_with( "Bad rcon_password" ) =>
+            Err( io::Error::new( io::ErrorKind::PermissionDenied, tr!( "The server refused the RCON password" ) ) ),
+        o if o.starts_with( "Bad challenge" ) =>
+            Err( io::Error::new( io::ErrorKind::InvalidData, tr!( "The server refused the RCON challenge" ) ) ),
+        o => Ok( o.to_string() )
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use std::thread;
+
+    // Answers one challenge and one command the way a server does, then hands back the command it was sent
+    fn mock_server(password: &'static str, output: &'static str) -> (u16, thread::JoinHandle<String>)
+    {
+        let socket = UdpSocket::bind( "127.0.0.1:0" ).unwrap();
+        let port = socket.local_addr().unwrap().port();
+
+        let server = thread::spawn( move ||
+        {
+            let mut buffer = [0u8; 4096];
+
+            let ( len, client ) = socket.recv_from( &mut buffer ).unwrap();
+            assert_eq!( &buffer[..len], packet( "challenge rcon" ).as_slice() );
+            socket.send_to( &packet( "challenge rcon 123456" ), client ).unwrap();
+
+            let ( len, client ) = socket.recv_from( &mut buffer ).unwrap();
+            let command = String::from_utf8_lossy( &buffer[HEADER.len()..len] ).trim_end().to_string();
+
+            let reply =
+            match command.starts_with( &format!( "rcon 123456 \"{}\" ", password ) )
+            {
+                true => format!( "l{}", output ),
+                false => "lBad rcon_password.\n".to_string()
+            };
+
+            socket.send_to( &[HEADER, reply.as_bytes()].concat(), client ).unwrap();
+
+            command
+        });
+
+        ( port, server )
+    }
+
+    fn settings(port: u16, password: &str) -> RconSettings
+    {
+        RconSettings { host: "127.0.0.1".to_string(), port, password: password.to_string(), ..Default::default() }
+    }
+
+    #[test]
+    fn send_command_returns_the_reply()
+    {
+        let ( port, server ) = mock_server( "secret", "Reloaded 3 plugins\n" );
+
+        let reply = send_command( &settings( port, "secret" ), RELOAD_COMMAND ).unwrap();
+
+        assert_eq!( reply, "Reloaded 3 plugins" );
+        assert_eq!( server.join().unwrap(), format!( "rcon 123456 \"secret\" {}", RELOAD_COMMAND ) );
+    }
+
+    #[test]
+    fn send_command_wrong_password()
+    {
+        let ( port, server ) = mock_server( "secret", "" );
+
+        let error = send_command( &settings( port, "guess" ), RELOAD_COMMAND ).unwrap_err();
+
+        assert_eq!( error.kind(), io::ErrorKind::PermissionDenied );
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn send_command_times_out()
+    {   // Bound but never answered, like a server that is down or blocks the port
+        let silent = UdpSocket::bind( "127.0.0.1:0" ).unwrap();
+        let port = silent.local_addr().unwrap().port();
+
+        let error = send_command( &settings( port, "secret" ), RELOAD_COMMAND ).unwrap_err();
+
+        assert_eq!( error.kind(), io::ErrorKind::TimedOut );
+    }
+}
@@ -0,0 +1,250 @@
+/*
+	Sven Co-op Plugin Manager Version 2.0
+
+Copyright (C) 2025 Outerbeast
+This program is free software: you can redistribute it and/or modify
+it under the terms of the GNU General Public License as published by
+the Free Software Foundation, either version 3 of the License, or
+(at your option) any later version.
+
+This program is distributed in the hope that it will be useful,
+but WITHOUT ANY WARRANTY; without even the implied warranty of
+MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+GNU General Public License for more details.
+
+You should have received a copy of the GNU General Public License
+along with this program. If not, see <https://www.gnu.org/licenses/>.
+*/
+use std::
+{
+    env,
+    fs,
+    path::{ Path, PathBuf }
+};
+
+pub const APPID_SVENCOOP: u32 = 225840;
+pub const APPID_SVENCOOP_DS: u32 = 276060;// Dedicated server
+// Valve KeyValues text, as used by libraryfolders.vdf and appmanifest_*.acf
+#[derive( Debug, Clone )]
+pub enum KeyValue
+{
+    Value( String ),
+    Block( Vec<(String, KeyValue)> )
+}
+
+impl KeyValue
+{
+    pub fn get(&self, key: &str) -> Option<&KeyValue>
+    {
+        match self
+        {
+            KeyValue::Block( items ) => items.iter().find( |(k, _)| k.eq_ignore_ascii_case( key ) ).map( |(_, v)| v ),
+            KeyValue::Value( _ ) => None
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str>
+    {
+        match self
+        {
+            KeyValue::Value( s ) => Some( s ),
+            KeyValue::Block( _ ) => None
+        }
+    }
+
+    pub fn items(&self) -> &[(String, KeyValue)]
+    {
+        match self
+        {
+            KeyValue::Block( items ) => items,
+            KeyValue::Value( _ ) => &[]
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Vec<String>
+{
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some( c ) = chars.next()
+    {
+        match c
+        {
+            '{' | '}' => tokens.push( c.to_string() ),
+            '"' =>
+            {
+                let mut s = String::new();
+
+                while let Some( c ) = chars.next()
+                {
+                    match c
+                    {
+                        '"' => break,
+                        '\\' => if let Some( escaped ) = chars.next() { s.push( escaped ) },
+                        _ => s.push( c )
+                    }
+                }
+                // Quoted braces are values, mark them so they aren't read as blocks
+                tokens.push( format!( "\"{}", s ) );
+            }
+            '/' if chars.peek() == Some( &'/' ) =>
+            {
+                while chars.next_if( |c| *c != '\n' ).is_some() {}
+            }
+            c if c.is_whitespace() => {}
+            _ =>
+            {
+                let mut s = String::from( c );
+
+                while let Some( c ) = chars.next_if( |c| !c.is_whitespace() && *c != '{' && *c != '}' && *c != '"' )
+                {
+                    s.push( c );
+                }
+
+                tokens.push( format!( "\"{}", s ) );
+            }
+        }
+    }
+
+    tokens
+}
+
+fn parse_block(tokens: &mut std::vec::IntoIter<String>) -> Vec<(String, KeyValue)>
+{
+    let mut items = Vec::new();
+
+    while let Some( key ) = tokens.next()
+    {
+        let Some( key ) = key.strip_prefix( '"' ).map( str::to_string ) else { break };// closing brace
+
+        match tokens.next()
+        {
+            Some( t ) if t == "{" => items.push( ( key, KeyValue::Block( parse_block( tokens ) ) ) ),
+            Some( t ) if t.starts_with( '"' ) => items.push( ( key, KeyValue::Value( t[1..].to_string() ) ) ),
+            _ => break
+        }
+    }
+
+    items
+}
+
+pub fn parse_keyvalues(text: &str) -> KeyValue
+{
+    KeyValue::Block( parse_block( &mut tokenize( text ).into_iter() ) )
+}
+// Where Steam itself is usually installed
+pub fn steam_roots() -> Vec<PathBuf>
+{
+    let mut roots = Vec::new();
+
+    #[cfg(target_os = "windows")]
+    {
+        for var in ["ProgramFiles(x86)", "ProgramFiles"]
+        {
+            if let Ok( dir ) = env::var( var )
+            {
+                roots.push( PathBuf::from( dir ).join( "Steam" ) );
+            }
+        }
+
+        roots.push( PathBuf::from( "C:\\Program Files (x86)\\Steam" ) );
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        if let Some( home ) = dirs::home_dir()
+        {
+            roots.push( home.join( ".steam/steam" ) );
+            roots.push( home.join( ".steam/root" ) );
+            roots.push( home.join( ".local/share/Steam" ) );
+            roots.push( home.join( ".var/app/com.valvesoftware.Steam/.local/share/Steam" ) );// Flatpak
+            roots.push( home.join( "snap/steam/common/.local/share/Steam" ) );
+            roots.push( home.join( "Steam" ) );// SteamCMD default
+        }
+
+        if let Ok( data ) = env::var( "XDG_DATA_HOME" )
+        {
+            roots.push( PathBuf::from( data ).join( "Steam" ) );
+        }
+    }
+
+    dedup_existing( roots )
+}
+// Drops paths that don't exist, and duplicates (~/.steam/steam is usually a symlink to another root)
+fn dedup_existing(paths: Vec<PathBuf>) -> Vec<PathBuf>
+{
+    let mut seen = Vec::new();
+    let mut unique = Vec::new();
+
+    for path in paths
+    {
+        if let Ok( canonical ) = fs::canonicalize( &path ) && !seen.contains( &canonical )
+        {
+            seen.push( canonical );
+            unique.push( path );
+        }
+    }
+
+    unique
+}
+// Every Steam library folder listed by the Steam installs found
+pub fn library_folders() -> Vec<PathBuf>
+{
+    let mut libraries = Vec::new();
+
+    for root in steam_roots()
+    {
+        libraries.push( root.clone() );
+
+        for vdf in [root.join( "steamapps/libraryfolders.vdf" ), root.join( "config/libraryfolders.vdf" )]
+        {
+            let Ok( text ) = fs::read_to_string( &vdf ) else { continue };
+            let kv = parse_keyvalues( &text );
+
+            for (_, folders) in kv.items()
+            {
+                for (_, folder) in folders.items()
+                {   // Newer files have a block with a "path" key, older ones just have the path as the value
+                    let path = folder.get( "path" ).and_then( KeyValue::as_str ).or( folder.as_str() );
+
+                    if let Some( path ) = path && Path::new( path ).is_absolute()
+                    {
+                        libraries.push( PathBuf::from( path ) );
+                    }
+                }
+            }
+        }
+    }
+
+    dedup_existing( libraries )
+}
+// Install folder of an app in a library, read from its appmanifest
+pub fn app_install_dir(library: &Path, appid: u32) -> Option<PathBuf>
+{
+    let steamapps = library.join( "steamapps" );
+    let text = fs::read_to_string( steamapps.join( format!( "appmanifest_{}.acf", appid ) ) ).ok()?;
+    let kv = parse_keyvalues( &text );
+    let installdir = kv.get( "AppState" )?.get( "installdir" )?.as_str()?;
+
+    let dir = steamapps.join( "common" ).join( installdir );
+    dir.is_dir().then_some( dir )
+}
+// "svencoop" folders of the client and dedicated server in every Steam library
+pub fn find_svencoop_dirs() -> Vec<PathBuf>
+{
+    let mut found = Vec::new();
+
+    for library in library_folders()
+    {
+        for appid in [APPID_SVENCOOP, APPID_SVENCOOP_DS]
+        {
+            if let Some( dir ) = app_install_dir( &library, appid )
+            {
+                found.push( dir.join( "svencoop" ) );
+            }
+        }
+    }
+
+    found
+}
@@ -15,7 +15,14 @@ GNU General Public License for more details.
 You should have received a copy of the GNU General Public License
 along with this program. If not, see <https://www.gnu.org/licenses/>.
 */
-use std::path::PathBuf;
+use std::
+{
+    fs,
+    io::{ self, Write },
+    path::{ Path, PathBuf },
+    process::Command,
+    sync::atomic::{ AtomicBool, Ordering }
+};
 
 #[macro_export]
 macro_rules! alloc_shared
@@ -34,108 +41,446 @@ macro_rules! alloc_locked
     };
 }
 
-#[cfg(target_os = "windows")]
-unsafe extern "system"
+// Fuzzy match ignoring case: the pattern's characters have to appear in the text in order, but not necessarily together
+pub fn fuzzy_match(pattern: &str, text: &str) -> bool
 {
-    fn AllocConsole() -> i32;
-    fn FreeConsole() -> i32;
+    let mut text = text.chars().flat_map( char::to_lowercase );
+
+    pattern
+        .chars()
+        .flat_map( char::to_lowercase )
+    .all( |c| text.any( |t| t == c ) )
 }
+// Splits a command line into the program and its arguments. Double quotes group words with spaces in them.
+pub fn split_command(command: &str) -> Vec<String>
+{
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut started = false;
+
+    for c in command.chars()
+    {
+        match c
+        {
+            '"' =>
+            {
+                quoted = !quoted;
+                started = true;
+            }
+
+            c if c.is_whitespace() && !quoted =>
+            {
+                if started
+                {
+                    parts.push( std::mem::take( &mut current ) );
+                    started = false;
+                }
+            }
+
+            c =>
+            {
+                current.push( c );
+                started = true;
+            }
+        }
+    }
+
+    if started
+    {
+        parts.push( current );
+    }
 
-#[cfg(target_os = "windows")]
-pub fn open_terminal()
+    parts
+}
+// Opens a file or folder with whatever the system has set up for it
+pub fn open_with_system(path: &Path) -> io::Result<()>
+{
+    let program =
+    match std::env::consts::OS
+    {
+        "windows" => "explorer",
+        "macos" => "open",
+        _ => "xdg-open"
+    };
+
+    Command::new( program ).arg( path ).spawn().map( |_| () )
+}
+// Runs the editor command on a file. "{file}" in the command is replaced by the path, otherwise the path goes on the end.
+// With no command set the system's default app for the file is used.
+pub fn open_in_editor(editor_command: &str, path: &Path) -> io::Result<()>
 {
-    unsafe
+    let mut parts = split_command( editor_command );
+
+    if parts.is_empty()
     {
-        AllocConsole();
+        return open_with_system( path );
     }
+
+    let file = path.to_string_lossy();
+
+    match parts.iter().any( |p| p.contains( "{file}" ) )
+    {
+        true => parts.iter_mut().for_each( |p| *p = p.replace( "{file}", &file ) ),
+        false => parts.push( file.into_owned() )
+    }
+
+    Command::new( &parts[0] ).args( &parts[1..] ).spawn().map( |_| () )
 }
+// Shows the file in the system file manager, selected where the file manager can do that
+pub fn reveal_in_file_manager(path: &Path) -> io::Result<()>
+{
+    #[cfg(target_os = "windows")]
+    {
+        Command::new( "explorer" ).arg( format!( "/select,{}", path.display() ) ).spawn().map( |_| () )
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        Command::new( "open" ).arg( "-R" ).arg( path ).spawn().map( |_| () )
+    }
 
-#[cfg(target_os = "windows")]
-pub fn close_terminal()
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        open_with_system( path.parent().unwrap_or( path ) )
+    }
+}
+// Sibling file next to the target, used for staging and rollback copies
+fn sidecar_path(path: &Path, ext: &str) -> PathBuf
 {
-    unsafe
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push( format!( ".{}", ext ) );
+
+    path.with_file_name( name )
+}
+
+fn sync_dir(_dir: &Path)
+{   // Directory handles can't be opened for syncing on Windows, the rename is enough there
+    #[cfg(unix)]
+    if let Ok( d ) = fs::File::open( _dir )
     {
-        FreeConsole();
+        let _ = d.sync_all();
     }
 }
-// Searches all drives for a specific filename, returns the path to that file
-pub fn search_drives(file_name: &str) -> Option<PathBuf>
+// Writes a single file atomically: temp file, fsync, rename over the target.
+// There's only the one rename, so unlike commit_files nothing is left to recover if it's cut off.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()>
 {
-    if file_name.trim().is_empty()
+    let tmp = sidecar_path( path, "tmp" );
+
+    let staged = fs::File::create( &tmp ).and_then( |mut f|
+    {
+        f.write_all( contents )?;
+        f.sync_all()
+    })
+    .and_then( |_| fs::rename( &tmp, path ) );
+
+    if staged.is_err()
     {
-        return None;
+        let _ = fs::remove_file( &tmp );
     }
 
-    #[cfg(target_os = "windows")]
+    if let Some( dir ) = path.parent()
+    {
+        sync_dir( dir );
+    }
+
+    staged
+}
+// Replaces a set of files together. Every file is staged and flushed to disk before any target is touched,
+// and if one of the renames fails the targets already replaced are rolled back from their backups.
+// A crash part way through the renames leaves a journal next to the first file, recover_commit puts
+// the files back as they were from it.
+pub fn commit_files(files: &[(&Path, &[u8])]) -> io::Result<()>
+{
+    let Some( (first, _) ) = files.first() else { return Ok(()) };
+    let journal = sidecar_path( first, "commit" );
+
+    let mut staged: Vec<PathBuf> = Vec::with_capacity( files.len() );
+    let mut backups: Vec<Option<PathBuf>> = Vec::with_capacity( files.len() );
+
+    let cleanup = |paths: &mut dyn Iterator<Item = &PathBuf>|
+    {
+        for p in paths
+        {
+            let _ = fs::remove_file( p );
+        }
+    };
+    // Stage everything first - nothing live has been modified if this fails
+    for (path, contents) in files
     {
-        for d in 'A'..='Z'
+        let tmp = sidecar_path( path, "tmp" );
+        let result = fs::File::create( &tmp ).and_then( |mut f|
         {
-            let drive = format!("{}:/", d);
-            let root = std::path::Path::new( &drive );
+            f.write_all( contents )?;
+            f.sync_all()
+        });
 
-            if !root.exists() || !root.is_dir()
-            {
-                continue;
-            }
+        staged.push( tmp );
 
-            let mut walker = walkdir::WalkDir::new( root )
-                .max_depth( 12 )
-                .into_iter()
-                .filter_entry( |e|
-                {
-                    let name = e.file_name().to_string_lossy();
-                    !name.eq_ignore_ascii_case( "$Recycle.Bin" )
-                })
-                .filter_map(Result::ok)
-                .filter( |e|
-                {
-                    e.file_name().to_string_lossy().eq_ignore_ascii_case( file_name )
-                });
+        if let Err( e ) = result
+        {
+            cleanup( &mut staged.iter() );
+            return Err( e );
+        }
+    }
+    // Keep copies of the current files so a half finished commit can be undone
+    for (path, _) in files
+    {
+        if !path.is_file()
+        {
+            backups.push( None );
+            continue;
+        }
+
+        let bak = sidecar_path( path, "bak" );
+
+        if let Err( e ) = fs::copy( path, &bak )
+        {
+            cleanup( &mut staged.iter().chain( backups.iter().flatten() ) );
+            return Err( e );
+        }
+
+        backups.push( Some( bak ) );
+    }
+    // Written once the backups are safe, from here on a crash is undone on the next start
+    let entries: String = files
+        .iter()
+        .zip( &backups )
+        .map( |((path, _), bak)| format!( "{}\t{}\n", if bak.is_some() { "bak" } else { "new" }, path.display() ) )
+    .collect();
 
-            if let Some( entry ) = walker.next()
+    if let Err( e ) = fs::File::create( &journal ).and_then( |mut f|
+    {
+        f.write_all( entries.as_bytes() )?;
+        f.sync_all()
+    })
+    {
+        cleanup( &mut staged.iter().chain( backups.iter().flatten() ).chain( std::iter::once( &journal ) ) );
+        return Err( e );
+    }
+
+    if let Some( dir ) = first.parent()
+    {
+        sync_dir( dir );
+    }
+
+    for (i, ((path, _), tmp)) in files.iter().zip( &staged ).enumerate()
+    {
+        if let Err( e ) = fs::rename( tmp, path )
+        {   // Put back whatever was already replaced
+            for ((restored, _), backup) in files[..i].iter().zip( &backups )
             {
-                return Some( entry.path().to_path_buf() );
+                let _ =
+                match backup
+                {
+                    Some( bak ) => fs::rename( bak, restored ),
+                    None => fs::remove_file( restored )
+                };
             }
+
+            cleanup( &mut staged[i..].iter().chain( backups[i..].iter().flatten() ).chain( std::iter::once( &journal ) ) );
+            return Err( e );
+        }
+    }
+    // Every file is in place, the journal goes first so a crash now doesn't roll a finished commit back
+    cleanup( &mut std::iter::once( &journal ).chain( backups.iter().flatten() ) );
+
+    for (path, _) in files
+    {
+        if let Some( dir ) = path.parent()
+        {
+            sync_dir( dir );
+        }
+    }
+
+    Ok(())
+}
+// Undoes a commit_files that didn't finish, e.g. the app or the system went down between the renames.
+// `first` is the first file of the set that was committed. Returns whether there was anything to undo.
+pub fn recover_commit(first: &Path) -> io::Result<bool>
+{
+    let journal = sidecar_path( first, "commit" );
+
+    let Ok( entries ) = fs::read_to_string( &journal ) else { return Ok( false ) };
+
+    for line in entries.lines()
+    {
+        let Some( (kind, path) ) = line.split_once( '\t' ) else { continue };
+        let path = Path::new( path );
+        let bak = sidecar_path( path, "bak" );
+
+        match kind
+        {
+            "bak" if bak.is_file() => fs::rename( &bak, path )?,
+            "new" if path.is_file() => fs::remove_file( path )?,
+            _ => {}
         }
+
+        let _ = fs::remove_file( sidecar_path( path, "tmp" ) );
+    }
+
+    fs::remove_file( &journal )?;
+
+    if let Some( dir ) = first.parent()
+    {
+        sync_dir( dir );
+    }
+
+    Ok( true )
+}
+// Mount points of network and FUSE filesystems. Walking one is slow, and hangs if its server has gone away.
+// Local disks mounted through FUSE (fuseblk, e.g. an NTFS drive with a Steam library on it) are still walked.
+#[cfg(target_os = "linux")]
+fn remote_mounts() -> Vec<PathBuf>
+{
+    const REMOTE: &[&str] = &["nfs", "nfs4", "cifs", "smb3", "smbfs", "9p", "afs", "ceph", "glusterfs", "davfs", "autofs"];
+
+    let Ok( mounts ) = fs::read_to_string( "/proc/self/mounts" ) else { return Vec::new() };
+
+    mounts.lines().filter_map( |line|
+    {
+        let mut fields = line.split_whitespace();
+        let ( point, kind ) = ( fields.nth( 1 )?, fields.next()? );
+        let remote = REMOTE.contains( &kind ) || ( kind.starts_with( "fuse" ) && kind != "fuseblk" );
+        // Spaces and the like are written as octal escapes
+        remote.then( || PathBuf::from( point.replace( "\\040", " " ).replace( "\\011", "\t" ).replace( "\\134", "\\" ) ) )
+    })
+    .collect()
+}
+// Walks all drives looking for a specific filename. `progress` is told about every folder entered and `found` gets the path
+// of each matching file. The walk stops early once `cancel` is set.
+pub fn walk_drives<P, F>(file_name: &str, cancel: &AtomicBool, mut progress: P, mut found: F)
+where P: FnMut(&Path), F: FnMut(&Path)
+{
+    if file_name.trim().is_empty()
+    {
+        return;
     }
 
+    #[cfg(target_os = "windows")]
+    let search_paths: Vec<PathBuf> = ('A'..='Z').map( |d| PathBuf::from( format!( "{}:/", d ) ) ).collect();
+
     #[cfg(target_os = "linux")]
+    let search_paths: Vec<PathBuf> = vec!
+    [
+        dirs::home_dir()
+            .map( |p| p.join( ".steam" ).join( "steam" ) )
+            .unwrap_or_default(),
+        PathBuf::from( "/mnt" ),
+        PathBuf::from( "/opt" ),
+        PathBuf::from( "/usr" ).join( "games" ),
+        PathBuf::from( "/" ),
+    ];
+
+    #[cfg(not(any(target_os = "windows", target_os = "linux")))]
+    let search_paths: Vec<PathBuf> = Vec::new();
+
+    // Kernel and device filesystems never hold the game, and reading them can stall
+    #[allow(unused_mut)]
+    let mut skipped: Vec<PathBuf> = ["/proc", "/sys", "/dev", "/run"].iter().map( PathBuf::from ).collect();
+
+    #[cfg(target_os = "linux")]
+    skipped.extend( remote_mounts() );
+
+    for (i, root) in search_paths.iter().enumerate()
     {
-        let search_paths: Vec<PathBuf> = vec!
-        [
-            PathBuf::from( "/" ),
-            dirs::home_dir()
-                .map( |p| p.join( ".steam" ).join( "steam" ) )
-                .unwrap_or_default(),
-            PathBuf::from( "/mnt" ),
-            PathBuf::from( "/opt" ),
-            PathBuf::from( "/usr" ).join( "games" ),
-        ];
-
-        for root in search_paths
+        if !root.exists() || !root.is_dir()
         {
-            if !root.exists() || !root.is_dir()
+            continue;
+        }
+        // Roots searched before this one are inside "/", they aren't gone through a second time
+        let walked = &search_paths[..i];
+
+        let walker = walkdir::WalkDir::new( root )
+            .max_depth( 12 )
+            .into_iter()
+            .filter_entry( |e|
             {
-                continue;
-            }
+                let name = e.file_name().to_string_lossy();
+                !name.starts_with( '.' )
+                && !name.eq_ignore_ascii_case( "$Recycle.Bin" )
+                && !skipped.iter().chain( walked ).any( |p| e.path() == p )
+            })
+        .filter_map( Result::ok );
 
-            let mut walker = walkdir::WalkDir::new( &root )
-                .max_depth( 12 )
-                .into_iter()
-                .filter_entry( |e|
-                {
-                    let name = e.file_name().to_string_lossy();
-                    !name.starts_with('.')
-                })
-                .filter_map( Result::ok )
-                .filter( |e| e.file_name().to_string_lossy() == file_name );
+        for entry in walker
+        {
+            if cancel.load( Ordering::Relaxed )
+            {
+                return;
+            }
 
-            if let Some(entry) = walker.next()
+            match entry.file_type().is_dir()
             {
-                return Some( entry.path().to_path_buf() );
+                true => progress( entry.path() ),
+                false if entry.file_name().to_string_lossy().eq_ignore_ascii_case( file_name ) => found( entry.path() ),
+                false => {}
             }
         }
     }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    // An empty folder of its own for each test, so they can run side by side
+    fn scratch_dir(name: &str) -> PathBuf
+    {
+        let dir = std::env::temp_dir().join( format!( "scpm-utils-{}-{}", name, std::process::id() ) );
+        let _ = fs::remove_dir_all( &dir );
+        fs::create_dir_all( &dir ).unwrap();
+
+        dir
+    }
+
+    #[test]
+    fn commit_files_replaces_every_file_and_cleans_up()
+    {
+        let dir = scratch_dir( "commit" );
+        let ( a, b ) = ( dir.join( "a.txt" ), dir.join( "b.txt" ) );
+        fs::write( &a, "old" ).unwrap();
+
+        commit_files( &[( &a, b"new a" ), ( &b, b"new b" )] ).unwrap();
+
+        assert_eq!( fs::read_to_string( &a ).unwrap(), "new a" );
+        assert_eq!( fs::read_to_string( &b ).unwrap(), "new b" );
+        assert_eq!( fs::read_dir( &dir ).unwrap().count(), 2, "no journal, backups or staged files left behind" );
+        assert!( !recover_commit( &a ).unwrap() );
+    }
 
-    None
+    #[test]
+    fn recover_commit_undoes_an_interrupted_commit()
+    {
+        let dir = scratch_dir( "recover" );
+        let ( a, b ) = ( dir.join( "a.txt" ), dir.join( "b.txt" ) );
+        // What a crash after the first rename leaves: a replaced with its backup kept, b new, the journal still there
+        fs::write( &a, "new a" ).unwrap();
+        fs::write( sidecar_path( &a, "bak" ), "old a" ).unwrap();
+        fs::write( &b, "new b" ).unwrap();
+        fs::write( sidecar_path( &b, "tmp" ), "new b" ).unwrap();
+        fs::write( sidecar_path( &a, "commit" ), format!( "bak\t{}\nnew\t{}\n", a.display(), b.display() ) ).unwrap();
+
+        assert!( recover_commit( &a ).unwrap() );
+
+        assert_eq!( fs::read_to_string( &a ).unwrap(), "old a" );
+        assert!( !b.exists() );
+        assert_eq!( fs::read_dir( &dir ).unwrap().count(), 1 );
+        assert!( !recover_commit( &a ).unwrap(), "nothing left to undo the second time" );
+    }
+
+    #[test]
+    fn write_atomic_leaves_no_journal()
+    {
+        let dir = scratch_dir( "atomic" );
+        let path = dir.join( "config.toml" );
+
+        write_atomic( &path, b"one" ).unwrap();
+        write_atomic( &path, b"two" ).unwrap();
+
+        assert_eq!( fs::read_to_string( &path ).unwrap(), "two" );
+        assert_eq!( fs::read_dir( &dir ).unwrap().count(), 1 );
+    }
 }